@@ -1,15 +1,11 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-/// `{ "rows": [ ... ] }` to `[ ... ]`
-macro_rules! unwrap_rows {
-    ($resp:ident, $ty:ty) => {{
-        #[derive(serde::Deserialize)]
-        struct __Rows {
-            rows: Vec<$ty>,
-        }
-
-        $resp.json::<__Rows>().await.unwrap().rows
-    }};
+/// `{ "rows": [ ... ] }`
+///
+/// use `.rows` to get `[ ... ]`
+#[derive(Deserialize, Serialize)]
+struct Rows<T> {
+    rows: Vec<T>,
 }
 
 /// impl Serialize for nested query
@@ -37,6 +33,32 @@ macro_rules! nested_query {
     };
 }
 
+/// impl `timeout` and `deadline` setters overriding [`DfClient::with_timeout`] and [`DfClient::with_deadline`]
+///
+/// `$client` is the path to the [`DfClient`] field, e.g. `client`
+///
+/// [`DfClient`]: crate::DfClient
+/// [`DfClient::with_timeout`]: crate::DfClient::with_timeout
+/// [`DfClient::with_deadline`]: crate::DfClient::with_deadline
+macro_rules! request_options {
+    ($target:ty; $($client:ident).+) => {
+        /// # Request Options
+        impl $target {
+            /// Each call fails with [`ErrorKind::Timeout`](crate::ErrorKind::Timeout) after `timeout`.
+            pub fn timeout(&mut self, timeout: std::time::Duration) -> &mut Self {
+                self.$($client).+.options.timeout = Some(timeout);
+                self
+            }
+
+            /// Calls fail with [`ErrorKind::Timeout`](crate::ErrorKind::Timeout) after `deadline`.
+            pub fn deadline(&mut self, deadline: tokio::time::Instant) -> &mut Self {
+                self.$($client).+.options.deadline = Some(deadline);
+                self
+            }
+        }
+    };
+}
+
 #[derive(Default, Clone, Copy, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum WordType {
@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
 use serde_with::SerializeDisplay;
 use urlencoding::encode;
 
@@ -10,7 +11,7 @@ use crate::{
     DfClient, Result,
 };
 
-use super::WordType;
+use super::{Rows, WordType};
 
 #[derive(Clone)]
 pub struct AuctionHandler {
@@ -31,22 +32,39 @@ impl AuctionHandler {
 /// # Send Request
 impl AuctionHandler {
     pub async fn search(&self) -> Result<Vec<AuctionInfo>> {
-        let url = self.make_url("/auction")?;
+        let resp: Rows<AuctionInfo> = self.search_as().await?;
+        Ok(resp.rows)
+    }
+
+    /// Raw JSON of [`AuctionHandler::search`].
+    pub async fn search_raw(&self) -> Result<Value> {
+        self.search_as().await
+    }
 
-        let resp = self.client.get_with_query(&url, Some(&self.param)).await?;
+    async fn search_as<T: DeserializeOwned + Serialize>(&self) -> Result<T> {
+        let url = self.make_url("/auction")?;
 
-        Ok(unwrap_rows!(resp, AuctionInfo))
+        self.client
+            .get_json_with_query("/auction", &url, Some(&self.param))
+            .await
     }
 
     pub async fn sold(&self) -> Result<Vec<SoldAuctionInfo>> {
-        let url = self.make_url("/auction-sold")?;
+        let resp: Rows<SoldAuctionInfo> = self.sold_as().await?;
+        Ok(resp.rows)
+    }
 
-        let resp = self
-            .client
-            .get_with_query(&url, Some(&self.param.to_sold_param()))
-            .await?;
+    /// Raw JSON of [`AuctionHandler::sold`].
+    pub async fn sold_raw(&self) -> Result<Value> {
+        self.sold_as().await
+    }
 
-        Ok(unwrap_rows!(resp, SoldAuctionInfo))
+    async fn sold_as<T: DeserializeOwned + Serialize>(&self) -> Result<T> {
+        let url = self.make_url("/auction-sold")?;
+
+        self.client
+            .get_json_with_query("/auction-sold", &url, Some(&self.param.to_sold_param()))
+            .await
     }
 
     fn make_url(&self, path: &str) -> Result<String> {
@@ -60,11 +78,13 @@ impl AuctionHandler {
         } else if !id.is_empty() {
             url.push_str(&format!("itemId={}", id));
         } else {
-            return Err(InvalidQueryParameter {
-                path: url.clone(),
-                message: "`item_name` or `item_id` must be specified.".to_owned(),
-            }
-            .into());
+            return Err(self.client.invalid_query(
+                path,
+                InvalidQueryParameter {
+                    path: url.clone(),
+                    message: "`item_name` or `item_id` must be specified.".to_owned(),
+                },
+            ));
         }
         Ok(url)
     }
@@ -303,3 +323,5 @@ impl Display for SortOrder {
         .fmt(f)
     }
 }
+
+request_options!(AuctionHandler; client);
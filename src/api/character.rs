@@ -1,6 +1,7 @@
 use bytes::Bytes;
 use futures::join;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
 use time::PrimitiveDateTime;
 use urlencoding::encode;
 
@@ -14,7 +15,7 @@ use crate::{
     DfClient, Result,
 };
 
-use super::WordType;
+use super::{Rows, WordType};
 
 #[derive(Clone)]
 pub struct CharacterHandler {
@@ -36,29 +37,38 @@ impl CharacterHandler {
 impl CharacterHandler {
     /// Search characters by name.
     pub async fn search(&self) -> Result<Vec<Character>> {
+        let resp: Rows<Character> = self.search_as().await?;
+        Ok(resp.rows)
+    }
+
+    /// Raw JSON of [`CharacterHandler::search`].
+    pub async fn search_raw(&self) -> Result<Value> {
+        self.search_as().await
+    }
+
+    async fn search_as<T: DeserializeOwned + Serialize>(&self) -> Result<T> {
         let name = &self.param.name;
         let server = self.param.server;
         if name.is_empty() {
-            return Err(InvalidQueryParameter {
-                path: format!("/servers/{server}/characters"),
-                message: "`characterName` must be specified.".to_owned(),
-            }
-            .into());
+            return Err(self.client.invalid_query(
+                "/servers/{server}/characters",
+                InvalidQueryParameter {
+                    path: format!("/servers/{server}/characters"),
+                    message: "`characterName` must be specified.".to_owned(),
+                },
+            ));
         }
         let param = &self.param;
-        let resp = self
-            .client
-            .get_with_query(
+        self.client
+            .get_json_with_query(
+                "/servers/{server}/characters",
                 &format!(
                     "/servers/{server}/characters?characterName={name}",
                     name = encode(name),
                 ),
                 Some(param),
             )
-            .await?;
-
-        let characters = unwrap_rows!(resp, Character);
-        Ok(characters)
+            .await
     }
 }
 
@@ -142,18 +152,19 @@ impl SpecificCharacterHandler {
 
 /// # Send Request
 impl SpecificCharacterHandler {
-    async fn get<T: DeserializeOwned>(&self, dst: &str) -> Result<T> {
-        let resp = self
-            .client
-            .get(&format!(
-                "/servers/{server}/characters/{id}/{dst}", // trailing slash is allowed
-                server = self.server,
-                id = self.character_id,
-                dst = dst,
-            ))
-            .await?;
-
-        Ok(resp.json().await.unwrap())
+    async fn get<T: DeserializeOwned + Serialize>(&self, dst: &str) -> Result<T> {
+        let endpoint = format!("/servers/{{server}}/characters/{{id}}/{dst}");
+        self.client
+            .get_json(
+                endpoint.trim_end_matches('/'),
+                &format!(
+                    "/servers/{server}/characters/{id}/{dst}", // trailing slash is allowed
+                    server = self.server,
+                    id = self.character_id,
+                    dst = dst,
+                ),
+            )
+            .await
     }
 
     /// Get character information.
@@ -161,10 +172,27 @@ impl SpecificCharacterHandler {
         self.get("").await
     }
 
+    /// Raw JSON of [`SpecificCharacterHandler::info`].
+    pub async fn info_raw(&self) -> Result<Value> {
+        self.get("").await
+    }
+
     pub async fn timeline(&self, param: Option<&TimelineParameter>) -> Result<CharacterTimeline> {
-        let resp = self
-            .client
-            .get_with_query(
+        self.timeline_as(param).await
+    }
+
+    /// Raw JSON of [`SpecificCharacterHandler::timeline`].
+    pub async fn timeline_raw(&self, param: Option<&TimelineParameter>) -> Result<Value> {
+        self.timeline_as(param).await
+    }
+
+    async fn timeline_as<T: DeserializeOwned + Serialize>(
+        &self,
+        param: Option<&TimelineParameter>,
+    ) -> Result<T> {
+        self.client
+            .get_json_with_query(
+                "/servers/{server}/characters/{id}/timeline",
                 &format!(
                     "/servers/{server}/characters/{id}/timeline",
                     server = self.server,
@@ -172,9 +200,7 @@ impl SpecificCharacterHandler {
                 ),
                 param,
             )
-            .await?;
-
-        Ok(resp.json().await.unwrap())
+            .await
     }
 
     /// Get character equipments.
@@ -182,26 +208,51 @@ impl SpecificCharacterHandler {
         self.get("equip/equipment").await
     }
 
+    /// Raw JSON of [`SpecificCharacterHandler::equipments`].
+    pub async fn equipments_raw(&self) -> Result<Value> {
+        self.get("equip/equipment").await
+    }
+
     /// Get character avatars.
     pub async fn avatars(&self) -> Result<CharacterAvatars> {
         self.get("equip/avatar").await
     }
 
+    /// Raw JSON of [`SpecificCharacterHandler::avatars`].
+    pub async fn avatars_raw(&self) -> Result<Value> {
+        self.get("equip/avatar").await
+    }
+
     /// Get character creature.
     pub async fn creature(&self) -> Result<CharacterCreature> {
         self.get("equip/creature").await
     }
 
+    /// Raw JSON of [`SpecificCharacterHandler::creature`].
+    pub async fn creature_raw(&self) -> Result<Value> {
+        self.get("equip/creature").await
+    }
+
     /// Get character flag.
     pub async fn flag(&self) -> Result<CharacterFlag> {
         self.get("equip/flag").await
     }
 
+    /// Raw JSON of [`SpecificCharacterHandler::flag`].
+    pub async fn flag_raw(&self) -> Result<Value> {
+        self.get("equip/flag").await
+    }
+
     /// Get character talismans.
     pub async fn talismans(&self) -> Result<CharacterTalismans> {
         self.get("equip/talisman").await
     }
 
+    /// Raw JSON of [`SpecificCharacterHandler::talismans`].
+    pub async fn talismans_raw(&self) -> Result<Value> {
+        self.get("equip/talisman").await
+    }
+
     /// Get character image.
     ///
     /// # Arguments
@@ -225,7 +276,7 @@ impl SpecificCharacterBuffHandler {
         Self { handler }
     }
 
-    async fn get(&self, dst: &str) -> Result<CharacterBuffEnhance> {
+    async fn get<T: DeserializeOwned + Serialize>(&self, dst: &str) -> Result<T> {
         self.handler.get(&format!("skill/buff/equip/{dst}")).await
     }
 
@@ -237,6 +288,11 @@ impl SpecificCharacterBuffHandler {
         self.get("equipment").await
     }
 
+    /// Raw JSON of [`SpecificCharacterBuffHandler::equipments`].
+    pub async fn equipments_raw(&self) -> Result<Value> {
+        self.get("equipment").await
+    }
+
     /// [`BuffEnhance::equipments`] and [`BuffEnhance::creature`] are always `None`.
     ///
     /// [`BuffEnhance::equipments`]: crate::model::buff::BuffEnhance#equipments
@@ -245,6 +301,11 @@ impl SpecificCharacterBuffHandler {
         self.get("avatar").await
     }
 
+    /// Raw JSON of [`SpecificCharacterBuffHandler::avatars`].
+    pub async fn avatars_raw(&self) -> Result<Value> {
+        self.get("avatar").await
+    }
+
     /// [`BuffEnhance::equipments`] and [`BuffEnhance::avatars`] are always `None`.
     ///
     /// [`BuffEnhance::equipments`]: crate::model::buff::BuffEnhance#equipments
@@ -253,6 +314,11 @@ impl SpecificCharacterBuffHandler {
         self.get("creature").await
     }
 
+    /// Raw JSON of [`SpecificCharacterBuffHandler::creature`].
+    pub async fn creature_raw(&self) -> Result<Value> {
+        self.get("creature").await
+    }
+
     /// Convenience method. using [`futures::join`].
     pub async fn all(&self) -> Result<CharacterBuffEnhance> {
         let (e, a, c) = join![self.equipments(), self.avatars(), self.creature()];
@@ -262,8 +328,28 @@ impl SpecificCharacterBuffHandler {
                 return Ok(e);
             }
             Some(buff) => {
-                buff.avatars = a?.buff.unwrap().avatars;
-                buff.creature = c?.buff.unwrap().creature;
+                buff.avatars = a?.buff.and_then(|b| b.avatars);
+                buff.creature = c?.buff.and_then(|b| b.creature);
+            }
+        }
+        Ok(e)
+    }
+
+    /// Raw JSON of [`SpecificCharacterBuffHandler::all`].
+    ///
+    /// `skill.buff.avatar` and `skill.buff.creature` are taken from the other responses.
+    pub async fn all_raw(&self) -> Result<Value> {
+        let (e, a, c) = join![
+            self.equipments_raw(),
+            self.avatars_raw(),
+            self.creature_raw()
+        ];
+        let mut e = e?;
+        let (a, c) = (a?, c?);
+        if let Some(Value::Object(buff)) = e.pointer_mut("/skill/buff") {
+            for (key, other) in [("avatar", &a), ("creature", &c)] {
+                let value = other.pointer(&format!("/skill/buff/{key}"));
+                buff.insert(key.to_owned(), value.cloned().unwrap_or(Value::Null));
             }
         }
         Ok(e)
@@ -300,3 +386,7 @@ time::serde::format_description!(
     PrimitiveDateTime,
     "[year]-[month]-[day] [hour]:[minute]"
 );
+
+request_options!(CharacterHandler; client);
+request_options!(SpecificCharacterHandler; client);
+request_options!(SpecificCharacterBuffHandler; handler.client);
@@ -12,9 +12,10 @@ pub struct ImageHandler {
     client: DfClient,
 }
 
-const BASE_URL: &str = "https://img-api.neople.co.kr/df";
-
 impl ImageHandler {
+    const CHARACTER: &'static str = "image:/servers/{server}/characters/{id}";
+    const ITEM: &'static str = "image:/items/{id}";
+
     pub(crate) fn new(client: DfClient) -> Self {
         Self { client }
     }
@@ -25,21 +26,25 @@ impl ImageHandler {
         character_id: &str,
         zoom: u8,
     ) -> crate::Result<Bytes> {
-        let url = format!("{BASE_URL}/servers/{server}/characters/{character_id}");
+        let url = self
+            .client
+            .image_url(&format!("/servers/{server}/characters/{character_id}"));
         if !(1..=3).contains(&zoom) {
-            return Err(InvalidQueryParameter {
-                path: url.clone(),
-                message: format!("`zoom` must be 1, 2, or 3. (current: `{zoom}`)"),
-            }
-            .into());
+            return Err(self.client.invalid_query(
+                Self::CHARACTER,
+                InvalidQueryParameter {
+                    path: url.clone(),
+                    message: format!("`zoom` must be 1, 2, or 3. (current: `{zoom}`)"),
+                },
+            ));
         }
 
         let response = self
             .client
-            .get_with_query(&url, Some(&[("zoom", zoom)]))
+            .get_with_query(Self::CHARACTER, &url, Some(&[("zoom", zoom)]))
             .await?;
 
-        Ok(response.bytes().await?)
+        Ok(response.body)
     }
 
     pub async fn character(&self, character: &Character, zoom: u8) -> crate::Result<Bytes> {
@@ -49,13 +54,18 @@ impl ImageHandler {
     pub async fn _item(&self, item_id: &str) -> crate::Result<Bytes> {
         let response = self
             .client
-            .get(&format!("{BASE_URL}/items/{item_id}"))
+            .get(
+                Self::ITEM,
+                &self.client.image_url(&format!("/items/{item_id}")),
+            )
             .await?;
 
-        Ok(response.bytes().await?)
+        Ok(response.body)
     }
 
     pub async fn item<T: AsItem>(&self, item: &T) -> crate::Result<Bytes> {
         self._item(item.id()).await
     }
 }
+
+request_options!(ImageHandler; client);
@@ -2,7 +2,8 @@ use std::fmt::Display;
 
 use bytes::Bytes;
 use itertools::join;
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
 use urlencoding::encode;
 
 use crate::{
@@ -11,7 +12,7 @@ use crate::{
     DfClient, Result,
 };
 
-use super::WordType;
+use super::{Rows, WordType};
 
 #[derive(Clone)]
 pub struct ItemHandler {
@@ -32,49 +33,77 @@ impl ItemHandler {
 /// # Send Request
 impl ItemHandler {
     pub async fn search(&self) -> Result<Vec<SearchItem>> {
+        let resp: Rows<SearchItem> = self.search_as().await?;
+        Ok(resp.rows)
+    }
+
+    /// Raw JSON of [`ItemHandler::search`].
+    pub async fn search_raw(&self) -> Result<Value> {
+        self.search_as().await
+    }
+
+    async fn search_as<T: DeserializeOwned + Serialize>(&self) -> Result<T> {
         let name = &self.param.item_name;
         if name.is_empty() {
-            return Err(InvalidQueryParameter {
-                path: "/items".to_owned(),
-                message: "`itemName` must be specified.".to_owned(),
-            }
-            .into());
+            return Err(self.client.invalid_query(
+                "/items",
+                InvalidQueryParameter {
+                    path: "/items".to_owned(),
+                    message: "`itemName` must be specified.".to_owned(),
+                },
+            ));
         }
-        let resp = self
-            .client
-            .get_with_query(
+        self.client
+            .get_json_with_query(
+                "/items",
                 &format!("/items?itemName={name}", name = encode(name)),
                 Some(&self.param),
             )
-            .await?;
-
-        Ok(unwrap_rows!(resp, SearchItem))
+            .await
     }
 
     pub async fn info(&self) -> Result<ItemInfo> {
-        let resp = self
-            .client
-            .get(&format!("/items/{id}", id = self.param.item_id))
-            .await?;
+        self.info_as().await
+    }
 
-        Ok(resp.json().await.unwrap())
+    /// Raw JSON of [`ItemHandler::info`].
+    pub async fn info_raw(&self) -> Result<Value> {
+        self.info_as().await
+    }
+
+    async fn info_as<T: DeserializeOwned + Serialize>(&self) -> Result<T> {
+        self.client
+            .get_json(
+                "/items/{id}",
+                &format!("/items/{id}", id = self.param.item_id),
+            )
+            .await
     }
 
     pub async fn multi_info(&self) -> Result<Vec<ItemInfo>> {
+        let resp: Rows<ItemInfo> = self.multi_info_as().await?;
+        Ok(resp.rows)
+    }
+
+    /// Raw JSON of [`ItemHandler::multi_info`].
+    pub async fn multi_info_raw(&self) -> Result<Value> {
+        self.multi_info_as().await
+    }
+
+    async fn multi_info_as<T: DeserializeOwned + Serialize>(&self) -> Result<T> {
         let id = &self.param.item_id;
         if id.is_empty() {
-            return Err(InvalidQueryParameter {
-                path: "/multi/items".to_owned(),
-                message: "`itemIds` must be specified. (use `id_iter()`)".to_owned(),
-            }
-            .into());
+            return Err(self.client.invalid_query(
+                "/multi/items",
+                InvalidQueryParameter {
+                    path: "/multi/items".to_owned(),
+                    message: "`itemIds` must be specified. (use `id_iter()`)".to_owned(),
+                },
+            ));
         }
-        let resp = self
-            .client
-            .get(&format!("/multi/items?itemIds={id}"))
-            .await?;
-
-        Ok(unwrap_rows!(resp, ItemInfo))
+        self.client
+            .get_json("/multi/items", &format!("/multi/items?itemIds={id}"))
+            .await
     }
 
     pub async fn image(&self) -> Result<Bytes> {
@@ -149,3 +178,5 @@ pub struct Query {
 }
 
 nested_query!(Query; min_level, max_level, rarity);
+
+request_options!(ItemHandler; client);
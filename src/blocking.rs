@@ -0,0 +1,565 @@
+//! Blocking counterpart of [`DfClient`](crate::DfClient). Enabled by `blocking` feature.
+//!
+//! Every call blocks the current thread on a tokio runtime owned by the client.
+//! Calling from async code panics, use the async [`DfClient`](crate::DfClient) there.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), df_rs::Error> {
+//! let client = df_rs::blocking::DfClient::new("<YOUR_API_KEY>");
+//! let items = client.item().name("haystack").search()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt::Display, sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use serde_json::Value;
+use tokio::runtime::Runtime;
+
+use crate::{
+    api::{
+        self,
+        auction::{AuctionSearchParameter, Query, Sort, SortOrder},
+        character::TimelineParameter,
+        WordType,
+    },
+    cache::CacheConfig,
+    cassette::CassetteMode,
+    concurrency::Priority,
+    key_pool::KeySelection,
+    maintenance::MaintenanceConfig,
+    middleware::Middleware,
+    model::{
+        buff::CharacterBuffEnhance, AuctionInfo, Character, CharacterAvatars, CharacterCreature,
+        CharacterEquipments, CharacterFlag, CharacterInfo, CharacterTalismans, CharacterTimeline,
+        ItemInfo, ItemRarity, SearchItem, Server, SoldAuctionInfo,
+    },
+    quota::QuotaConfig,
+    rate_limit::RateLimit,
+    retry::RetryPolicy,
+    transport::Transport,
+    util::AsItem,
+    CancellationToken, Result,
+};
+
+/// impl setters delegating to the async handler in `self.inner`
+macro_rules! setters {
+    ($($(#[$meta:meta])* fn $name:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(&mut self, $($arg: $ty),*) -> &mut Self {
+                self.inner.$name($($arg),*);
+                self
+            }
+        )*
+    };
+}
+
+/// impl methods blocking on the async method of `self.inner`
+macro_rules! blocking {
+    ($($(#[$meta:meta])* fn $name:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(&self, $($arg: $ty),*) -> Result<$ret> {
+                self.runtime.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+/// Blocking client of [Dungeon & Fighter API](https://developers.neople.co.kr/contents/apiDocs/df).
+///
+/// Clones share the runtime, the connection pool and every limit of the client.
+#[derive(Debug, Clone)]
+pub struct DfClient {
+    inner: crate::DfClient,
+    runtime: Arc<Runtime>,
+}
+
+/// # Constructor
+impl DfClient {
+    /// # Panics
+    /// Panics if `api_key` is not a valid header value,
+    /// or the underlying [`reqwest::Client`] or runtime cannot be built.
+    ///
+    /// Use [`DfClient::builder`] to handle these errors.
+    pub fn new(api_key: &str) -> Self {
+        Self::from_async(crate::DfClient::new(api_key))
+    }
+
+    pub fn builder() -> DfClientBuilder {
+        DfClientBuilder::new()
+    }
+
+    /// # Panics
+    /// Panics if the runtime cannot be built.
+    pub fn from_async(client: crate::DfClient) -> Self {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to build tokio runtime");
+        Self {
+            inner: client,
+            runtime: Arc::new(runtime),
+        }
+    }
+
+    /// The async client, e.g. for [`DfClient::key_usage`](crate::DfClient::key_usage).
+    pub fn as_async(&self) -> &crate::DfClient {
+        &self.inner
+    }
+}
+
+/// Builder of blocking [`DfClient`].
+///
+/// Same options as [`crate::DfClientBuilder`].
+///
+/// ```no_run
+/// # fn main() -> Result<(), df_rs::Error> {
+/// let client = df_rs::blocking::DfClient::builder()
+///     .api_key("<YOUR_API_KEY>")
+///     .connect_timeout(std::time::Duration::from_secs(3))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DfClientBuilder {
+    inner: crate::DfClientBuilder,
+}
+
+impl From<crate::DfClientBuilder> for DfClientBuilder {
+    fn from(inner: crate::DfClientBuilder) -> Self {
+        Self { inner }
+    }
+}
+
+/// # Constructor
+impl DfClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// # Option
+impl DfClientBuilder {
+    setters! {
+        fn api_key(api_key: impl Into<String>);
+        fn key_selection(selection: KeySelection);
+        fn key_cooldown(cooldown: Duration);
+        fn base_url(base_url: impl Into<String>);
+        fn image_base_url(image_base_url: impl Into<String>);
+        fn connect_timeout(timeout: Duration);
+        fn timeout(timeout: Duration);
+        fn user_agent(user_agent: impl Into<String>);
+        fn http_client(client: reqwest::Client);
+        fn retry(policy: RetryPolicy);
+        fn rate_limit(limit: RateLimit);
+        fn max_in_flight(max: usize);
+        fn maintenance(config: MaintenanceConfig);
+        fn quota(config: QuotaConfig);
+        fn cache(config: CacheConfig);
+        #[cfg(feature = "disk-cache")]
+        fn disk_cache(dir: impl Into<std::path::PathBuf>, config: CacheConfig);
+        fn schema_drift(enabled: bool);
+        fn single_flight(enabled: bool);
+        fn transport(transport: impl Transport);
+        fn cassette(mode: CassetteMode);
+        fn middleware(middleware: impl Middleware);
+    }
+
+    pub fn api_keys<I>(&mut self, api_keys: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.inner.api_keys(api_keys);
+        self
+    }
+}
+
+/// # Build
+impl DfClientBuilder {
+    /// # Errors
+    /// See [`crate::DfClientBuilder::build`].
+    ///
+    /// # Panics
+    /// Panics if the runtime cannot be built.
+    pub fn build(&self) -> Result<DfClient> {
+        self.inner.build_blocking()
+    }
+}
+
+/// # Request Options
+impl DfClient {
+    /// See [`DfClient::with_priority`](crate::DfClient::with_priority).
+    pub fn with_priority(&self, priority: Priority) -> Self {
+        self.map(|client| client.with_priority(priority))
+    }
+
+    /// See [`DfClient::with_timeout`](crate::DfClient::with_timeout).
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        self.map(|client| client.with_timeout(timeout))
+    }
+
+    /// See [`DfClient::with_deadline`](crate::DfClient::with_deadline).
+    pub fn with_deadline(&self, deadline: tokio::time::Instant) -> Self {
+        self.map(|client| client.with_deadline(deadline))
+    }
+
+    /// See [`DfClient::with_cancellation`](crate::DfClient::with_cancellation).
+    /// `token` can be cancelled from another thread.
+    pub fn with_cancellation(&self, token: CancellationToken) -> Self {
+        self.map(|client| client.with_cancellation(token))
+    }
+
+    fn map(&self, f: impl FnOnce(&crate::DfClient) -> crate::DfClient) -> Self {
+        Self {
+            inner: f(&self.inner),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// # Handlers
+impl DfClient {
+    pub fn character(&self) -> CharacterHandler {
+        CharacterHandler {
+            inner: self.inner.character(),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    pub fn item(&self) -> ItemHandler {
+        ItemHandler {
+            inner: self.inner.item(),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    pub fn auction(&self) -> AuctionHandler {
+        AuctionHandler {
+            inner: self.inner.auction(),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    pub fn image(&self) -> ImageHandler {
+        ImageHandler {
+            inner: self.inner.image(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking [`api::character::CharacterHandler`].
+#[derive(Clone)]
+pub struct CharacterHandler {
+    inner: api::character::CharacterHandler,
+    runtime: Arc<Runtime>,
+}
+
+/// # Send Request
+impl CharacterHandler {
+    blocking! {
+        /// Search characters by name.
+        fn search() -> Vec<Character>;
+        /// Raw JSON of [`CharacterHandler::search`].
+        fn search_raw() -> Value;
+    }
+}
+
+/// # Parameter
+impl CharacterHandler {
+    setters! {
+        fn name(character_name: impl Into<String>);
+        fn server(server: Server);
+        fn job_id(job_id: impl Into<String>);
+        fn job_grow_id(job_grow_id: impl Into<String>);
+        fn limit(limit: u8);
+        fn word_type(word_type: WordType);
+    }
+}
+
+/// # Request Options
+impl CharacterHandler {
+    setters! {
+        /// See [`api::character::CharacterHandler::timeout`].
+        fn timeout(timeout: Duration);
+        /// See [`api::character::CharacterHandler::deadline`].
+        fn deadline(deadline: tokio::time::Instant);
+    }
+}
+
+/// # Constructor of [`SpecificCharacterHandler`]
+impl CharacterHandler {
+    pub fn of(&self, character: &Character) -> SpecificCharacterHandler {
+        SpecificCharacterHandler {
+            inner: self.inner.of(character),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    pub fn _of(&self, server: Server, character_id: &str) -> SpecificCharacterHandler {
+        SpecificCharacterHandler {
+            inner: self.inner._of(server, character_id),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking [`api::character::SpecificCharacterHandler`].
+#[derive(Clone)]
+pub struct SpecificCharacterHandler {
+    inner: api::character::SpecificCharacterHandler,
+    runtime: Arc<Runtime>,
+}
+
+impl SpecificCharacterHandler {
+    pub fn server(&self) -> Server {
+        self.inner.server
+    }
+
+    pub fn character_id(&self) -> &str {
+        &self.inner.character_id
+    }
+
+    pub fn buff(&self) -> SpecificCharacterBuffHandler {
+        SpecificCharacterBuffHandler {
+            inner: self.inner.buff(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// # Send Request
+impl SpecificCharacterHandler {
+    blocking! {
+        /// Get character information.
+        fn info() -> CharacterInfo;
+        /// Raw JSON of [`SpecificCharacterHandler::info`].
+        fn info_raw() -> Value;
+        fn timeline(param: Option<&TimelineParameter>) -> CharacterTimeline;
+        /// Raw JSON of [`SpecificCharacterHandler::timeline`].
+        fn timeline_raw(param: Option<&TimelineParameter>) -> Value;
+        /// Get character equipments.
+        fn equipments() -> CharacterEquipments;
+        /// Raw JSON of [`SpecificCharacterHandler::equipments`].
+        fn equipments_raw() -> Value;
+        /// Get character avatars.
+        fn avatars() -> CharacterAvatars;
+        /// Raw JSON of [`SpecificCharacterHandler::avatars`].
+        fn avatars_raw() -> Value;
+        /// Get character creature.
+        fn creature() -> CharacterCreature;
+        /// Raw JSON of [`SpecificCharacterHandler::creature`].
+        fn creature_raw() -> Value;
+        /// Get character flag.
+        fn flag() -> CharacterFlag;
+        /// Raw JSON of [`SpecificCharacterHandler::flag`].
+        fn flag_raw() -> Value;
+        /// Get character talismans.
+        fn talismans() -> CharacterTalismans;
+        /// Raw JSON of [`SpecificCharacterHandler::talismans`].
+        fn talismans_raw() -> Value;
+        /// Get character image.
+        ///
+        /// # Arguments
+        ///
+        /// * `zoom` - Zoom level. 1 to 3.
+        fn image(zoom: u8) -> Bytes;
+    }
+}
+
+/// # Request Options
+impl SpecificCharacterHandler {
+    setters! {
+        /// See [`api::character::SpecificCharacterHandler::timeout`].
+        fn timeout(timeout: Duration);
+        /// See [`api::character::SpecificCharacterHandler::deadline`].
+        fn deadline(deadline: tokio::time::Instant);
+    }
+}
+
+/// Blocking [`api::character::SpecificCharacterBuffHandler`].
+#[derive(Clone)]
+pub struct SpecificCharacterBuffHandler {
+    inner: api::character::SpecificCharacterBuffHandler,
+    runtime: Arc<Runtime>,
+}
+
+/// # Send Request
+impl SpecificCharacterBuffHandler {
+    blocking! {
+        /// See [`api::character::SpecificCharacterBuffHandler::equipments`].
+        fn equipments() -> CharacterBuffEnhance;
+        /// Raw JSON of [`SpecificCharacterBuffHandler::equipments`].
+        fn equipments_raw() -> Value;
+        /// See [`api::character::SpecificCharacterBuffHandler::avatars`].
+        fn avatars() -> CharacterBuffEnhance;
+        /// Raw JSON of [`SpecificCharacterBuffHandler::avatars`].
+        fn avatars_raw() -> Value;
+        /// See [`api::character::SpecificCharacterBuffHandler::creature`].
+        fn creature() -> CharacterBuffEnhance;
+        /// Raw JSON of [`SpecificCharacterBuffHandler::creature`].
+        fn creature_raw() -> Value;
+        /// Requests are still sent concurrently.
+        fn all() -> CharacterBuffEnhance;
+        /// Raw JSON of [`SpecificCharacterBuffHandler::all`].
+        fn all_raw() -> Value;
+    }
+}
+
+/// # Request Options
+impl SpecificCharacterBuffHandler {
+    setters! {
+        /// See [`api::character::SpecificCharacterBuffHandler::timeout`].
+        fn timeout(timeout: Duration);
+        /// See [`api::character::SpecificCharacterBuffHandler::deadline`].
+        fn deadline(deadline: tokio::time::Instant);
+    }
+}
+
+/// Blocking [`api::item::ItemHandler`].
+#[derive(Clone)]
+pub struct ItemHandler {
+    inner: api::item::ItemHandler,
+    runtime: Arc<Runtime>,
+}
+
+/// # Send Request
+impl ItemHandler {
+    blocking! {
+        fn search() -> Vec<SearchItem>;
+        /// Raw JSON of [`ItemHandler::search`].
+        fn search_raw() -> Value;
+        fn info() -> ItemInfo;
+        /// Raw JSON of [`ItemHandler::info`].
+        fn info_raw() -> Value;
+        fn multi_info() -> Vec<ItemInfo>;
+        /// Raw JSON of [`ItemHandler::multi_info`].
+        fn multi_info_raw() -> Value;
+        fn image() -> Bytes;
+    }
+}
+
+/// # Parameter
+impl ItemHandler {
+    setters! {
+        fn name(name: impl Into<String>);
+        fn id(id: impl Into<String>);
+        fn limit(limit: u8);
+        fn word_type(word_type: WordType);
+        fn max_level(max_level: u8);
+        fn min_level(min_level: u8);
+        fn rarity(rarity: ItemRarity);
+    }
+
+    pub fn id_iter<I>(&mut self, ids: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        self.inner.id_iter(ids);
+        self
+    }
+}
+
+/// # Request Options
+impl ItemHandler {
+    setters! {
+        /// See [`api::item::ItemHandler::timeout`].
+        fn timeout(timeout: Duration);
+        /// See [`api::item::ItemHandler::deadline`].
+        fn deadline(deadline: tokio::time::Instant);
+    }
+}
+
+/// Blocking [`api::auction::AuctionHandler`].
+#[derive(Clone)]
+pub struct AuctionHandler {
+    inner: api::auction::AuctionHandler,
+    runtime: Arc<Runtime>,
+}
+
+/// # Send Request
+impl AuctionHandler {
+    blocking! {
+        fn search() -> Vec<AuctionInfo>;
+        /// Raw JSON of [`AuctionHandler::search`].
+        fn search_raw() -> Value;
+        fn sold() -> Vec<SoldAuctionInfo>;
+        /// Raw JSON of [`AuctionHandler::sold`].
+        fn sold_raw() -> Value;
+    }
+}
+
+/// # Parameter
+impl AuctionHandler {
+    setters! {
+        fn param(param: AuctionSearchParameter);
+        fn limit(limit: u16);
+        fn sort(sort: Sort);
+        fn sort_by_reinforce(sort: SortOrder);
+        fn sort_by_unit_price(sort: SortOrder);
+        fn sort_by_auction_no(sort: SortOrder);
+        fn id(item_id: impl Into<String>);
+        fn name(item_name: impl Into<String>);
+        fn word_type(word_type: WordType);
+        fn word_short(word_short: bool);
+        fn query(query: Query);
+        fn min_level(min_level: u8);
+        fn max_level(max_level: u8);
+        fn level(min: u8, max: u8);
+        fn rarity(rarity: ItemRarity);
+        fn min_reinforce(min_reinforce: u8);
+        fn max_reinforce(max_reinforce: u8);
+        fn reinforce(min: u8, max: u8);
+        fn min_refine(min_refine: u8);
+        fn max_refine(max_refine: u8);
+        fn refine(min: u8, max: u8);
+        fn min_adventure_fame(min_adventure_fame: u16);
+        fn max_adventure_fame(max_adventure_fame: u16);
+        fn adventure_fame(min: u16, max: u16);
+    }
+}
+
+/// # Request Options
+impl AuctionHandler {
+    setters! {
+        /// See [`api::auction::AuctionHandler::timeout`].
+        fn timeout(timeout: Duration);
+        /// See [`api::auction::AuctionHandler::deadline`].
+        fn deadline(deadline: tokio::time::Instant);
+    }
+}
+
+/// Blocking [`api::image::ImageHandler`].
+#[derive(Clone)]
+pub struct ImageHandler {
+    inner: api::image::ImageHandler,
+    runtime: Arc<Runtime>,
+}
+
+impl ImageHandler {
+    blocking! {
+        fn _character(server: Server, character_id: &str, zoom: u8) -> Bytes;
+        fn character(character: &Character, zoom: u8) -> Bytes;
+        fn _item(item_id: &str) -> Bytes;
+    }
+
+    pub fn item<T: AsItem>(&self, item: &T) -> Result<Bytes> {
+        self.runtime.block_on(self.inner.item(item))
+    }
+}
+
+/// # Request Options
+impl ImageHandler {
+    setters! {
+        /// See [`api::image::ImageHandler::timeout`].
+        fn timeout(timeout: Duration);
+        /// See [`api::image::ImageHandler::deadline`].
+        fn deadline(deadline: tokio::time::Instant);
+    }
+}
@@ -0,0 +1,380 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use zeroize::Zeroizing;
+
+use crate::{
+    cache::{CacheConfig, ResponseCache},
+    cassette::{CassetteMode, RecordingTransport, ReplayTransport},
+    concurrency::ConcurrencyLimiter,
+    key_pool::{self, KeyPool, KeySelection},
+    maintenance::{Maintenance, MaintenanceConfig},
+    middleware::{Middleware, Middlewares},
+    quota::{Quota, QuotaConfig},
+    rate_limit::{RateLimit, RateLimiter},
+    retry::RetryPolicy,
+    schema_drift::SchemaDrift,
+    single_flight::SingleFlight,
+    transport::{ReqwestTransport, Transport},
+    ClientInner, DfClient, Result, DF_BASE_URL, DF_IMAGE_BASE_URL,
+};
+
+/// Builder of [`DfClient`].
+///
+/// ```no_run
+/// # fn main() -> Result<(), df_rs::Error> {
+/// let client = df_rs::DfClient::builder()
+///     .api_key("<YOUR_API_KEY>")
+///     .base_url("http://localhost:8080/df")
+///     .connect_timeout(std::time::Duration::from_secs(3))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct DfClientBuilder {
+    /// Zeroed on drop.
+    api_keys: Vec<Zeroizing<String>>,
+    key_selection: KeySelection,
+    key_cooldown: Duration,
+    base_url: Option<String>,
+    image_base_url: Option<String>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    user_agent: Option<String>,
+    http_client: Option<reqwest::Client>,
+    transport: Option<Arc<dyn Transport>>,
+    middlewares: Middlewares,
+    cassette: Option<CassetteMode>,
+    retry: Option<RetryPolicy>,
+    rate_limit: Option<RateLimit>,
+    max_in_flight: Option<usize>,
+    maintenance: Option<MaintenanceConfig>,
+    quota: Option<QuotaConfig>,
+    schema_drift: bool,
+    cache: Option<CacheConfig>,
+    #[cfg(feature = "disk-cache")]
+    disk_cache: Option<(std::path::PathBuf, CacheConfig)>,
+    single_flight: bool,
+}
+
+impl Default for DfClientBuilder {
+    fn default() -> Self {
+        Self {
+            api_keys: Default::default(),
+            key_selection: Default::default(),
+            key_cooldown: Duration::from_secs(60),
+            base_url: None,
+            image_base_url: None,
+            connect_timeout: None,
+            timeout: None,
+            user_agent: None,
+            http_client: None,
+            transport: None,
+            middlewares: Middlewares::default(),
+            cassette: None,
+            retry: None,
+            rate_limit: None,
+            max_in_flight: None,
+            maintenance: None,
+            quota: None,
+            schema_drift: false,
+            cache: None,
+            #[cfg(feature = "disk-cache")]
+            disk_cache: None,
+            single_flight: true,
+        }
+    }
+}
+
+/// API keys are masked.
+impl fmt::Debug for DfClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let api_keys: Vec<String> = self
+            .api_keys
+            .iter()
+            .map(|key| key_pool::mask(key))
+            .collect();
+        f.debug_struct("DfClientBuilder")
+            .field("api_keys", &api_keys)
+            .field("key_selection", &self.key_selection)
+            .field("base_url", &self.base_url)
+            .field("image_base_url", &self.image_base_url)
+            .field("cassette", &self.cassette)
+            .finish_non_exhaustive()
+    }
+}
+
+/// # Constructor
+impl DfClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// # Option
+impl DfClientBuilder {
+    pub fn api_key(&mut self, api_key: impl Into<String>) -> &mut Self {
+        self.api_keys = vec![Zeroizing::new(api_key.into())];
+        self
+    }
+
+    /// Rotate among several API keys.
+    pub fn api_keys<I>(&mut self, api_keys: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.api_keys = api_keys
+            .into_iter()
+            .map(|key| Zeroizing::new(key.into()))
+            .collect();
+        self
+    }
+
+    /// Default: [`KeySelection::RoundRobin`]
+    pub fn key_selection(&mut self, selection: KeySelection) -> &mut Self {
+        self.key_selection = selection;
+        self
+    }
+
+    /// How long a key is not used after it fails with
+    /// [`ErrorCode::API002`], [`ErrorCode::API003`] or [`ErrorCode::API004`]. Default: 1 minute
+    ///
+    /// [`ErrorCode::API002`]: crate::error::ErrorCode::API002
+    /// [`ErrorCode::API003`]: crate::error::ErrorCode::API003
+    /// [`ErrorCode::API004`]: crate::error::ErrorCode::API004
+    pub fn key_cooldown(&mut self, cooldown: Duration) -> &mut Self {
+        self.key_cooldown = cooldown;
+        self
+    }
+
+    /// Default: `https://api.neople.co.kr/df`
+    pub fn base_url(&mut self, base_url: impl Into<String>) -> &mut Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Default: `https://img-api.neople.co.kr/df`
+    pub fn image_base_url(&mut self, image_base_url: impl Into<String>) -> &mut Self {
+        self.image_base_url = Some(image_base_url.into());
+        self
+    }
+
+    /// Ignored if [`http_client`](Self::http_client) or [`transport`](Self::transport) is set.
+    pub fn connect_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout of a whole request, from connecting until the response body is read.
+    ///
+    /// Ignored if [`http_client`](Self::http_client) or [`transport`](Self::transport) is set.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Ignored if [`http_client`](Self::http_client) or [`transport`](Self::transport) is set.
+    pub fn user_agent(&mut self, user_agent: impl Into<String>) -> &mut Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Use pre-built [`reqwest::Client`].
+    ///
+    /// `apikey` header is added to each request, so the client doesn't need it.
+    ///
+    /// Ignored if [`transport`](Self::transport) is set.
+    pub fn http_client(&mut self, client: reqwest::Client) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Retry failed requests. By default, requests are not retried.
+    pub fn retry(&mut self, policy: RetryPolicy) -> &mut Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Pace requests to stay under the API key quota. By default, requests are not limited.
+    ///
+    /// The limit is shared by all clones of the built client, and retries also take a token.
+    pub fn rate_limit(&mut self, limit: RateLimit) -> &mut Self {
+        self.rate_limit = Some(limit);
+        self
+    }
+
+    /// Limit the number of requests being sent at once. By default, not limited.
+    ///
+    /// Waiting requests are sent in order of [`Priority`], see [`DfClient::with_priority`].
+    /// Coalesced requests (see [`DfClientBuilder::single_flight`]) are sent with the priority of the first one.
+    ///
+    /// [`Priority`]: crate::concurrency::Priority
+    pub fn max_in_flight(&mut self, max: usize) -> &mut Self {
+        self.max_in_flight = Some(max);
+        self
+    }
+
+    /// Pause requests while the API is under maintenance (`DNF980`).
+    /// By default, `DNF980` is returned as is.
+    ///
+    /// See [`DfClient::maintenance`] and [`DfClient::subscribe_maintenance`].
+    pub fn maintenance(&mut self, config: MaintenanceConfig) -> &mut Self {
+        self.maintenance = Some(config);
+        self
+    }
+
+    /// Count requests per API key per day (KST). By default, requests are not counted.
+    ///
+    /// See [`DfClient::quota_usage`].
+    pub fn quota(&mut self, config: QuotaConfig) -> &mut Self {
+        self.quota = Some(config);
+        self
+    }
+
+    /// Cache successful responses in memory. By default, responses are not cached.
+    pub fn cache(&mut self, config: CacheConfig) -> &mut Self {
+        self.cache = Some(config);
+        self
+    }
+
+    /// Persist successful responses under `dir`. By default, responses are not persisted.
+    ///
+    /// Checked after the in-memory [`cache`](Self::cache).
+    #[cfg(feature = "disk-cache")]
+    pub fn disk_cache(
+        &mut self,
+        dir: impl Into<std::path::PathBuf>,
+        config: CacheConfig,
+    ) -> &mut Self {
+        self.disk_cache = Some((dir.into(), config));
+        self
+    }
+
+    /// Record fields of responses which are unknown to, or defaulted by the models.
+    /// Default: `false`
+    ///
+    /// See [`DfClient::schema_drift`].
+    pub fn schema_drift(&mut self, enabled: bool) -> &mut Self {
+        self.schema_drift = enabled;
+        self
+    }
+
+    /// Coalesce concurrent requests to the same url into one. Default: `true`
+    ///
+    /// Every waiter receives the result (or error) of the single request.
+    pub fn single_flight(&mut self, enabled: bool) -> &mut Self {
+        self.single_flight = enabled;
+        self
+    }
+
+    /// Use custom [`Transport`] instead of [`ReqwestTransport`].
+    pub fn transport(&mut self, transport: impl Transport) -> &mut Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Record responses to, or replay them from a cassette file. By default, requests are sent as is.
+    ///
+    /// Wraps the transport, inside the [middlewares](Self::middleware). See [`crate::cassette`].
+    pub fn cassette(&mut self, mode: CassetteMode) -> &mut Self {
+        self.cassette = Some(mode);
+        self
+    }
+
+    /// Append `middleware` to the chain around the transport.
+    pub fn middleware(&mut self, middleware: impl Middleware) -> &mut Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+}
+
+/// # Build
+impl DfClientBuilder {
+    /// # Errors
+    ///
+    /// - [`ErrorKind::InvalidApiKey`] if any API key is not a valid header value.
+    /// - [`ErrorKind::Reqwest`] if [`reqwest::Client`] cannot be built.
+    /// - [`ErrorKind::Cassette`] if the cassette to replay cannot be loaded.
+    ///
+    /// [`ErrorKind::InvalidApiKey`]: crate::ErrorKind::InvalidApiKey
+    /// [`ErrorKind::Reqwest`]: crate::ErrorKind::Reqwest
+    /// [`ErrorKind::Cassette`]: crate::ErrorKind::Cassette
+    pub fn build(&self) -> Result<DfClient> {
+        let no_key = [Zeroizing::new(String::new())];
+        let api_keys = match self.api_keys.as_slice() {
+            [] => &no_key[..],
+            keys => keys,
+        };
+        let keys = KeyPool::new(api_keys, self.key_selection, self.key_cooldown)?;
+
+        let transport = match (&self.transport, &self.http_client) {
+            (Some(transport), _) => transport.clone(),
+            (None, Some(client)) => Arc::new(ReqwestTransport::new(client.clone())),
+            (None, None) => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(user_agent) = &self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                Arc::new(ReqwestTransport::new(builder.build()?))
+            }
+        };
+        let transport: Arc<dyn Transport> = match &self.cassette {
+            None => transport,
+            Some(CassetteMode::Record(path)) => Arc::new(RecordingTransport::new(transport, path)),
+            Some(CassetteMode::Replay(path)) => Arc::new(ReplayTransport::load(path)?),
+        };
+
+        let quota = self.quota.map(|config| {
+            let masked = keys.usage().into_iter().map(|usage| usage.key).collect();
+            Quota::new(config, masked)
+        });
+
+        Ok(DfClient::from_inner(ClientInner {
+            transport,
+            middlewares: self.middlewares.clone(),
+            keys,
+            base_url: trim_base_url(self.base_url.as_deref().unwrap_or(DF_BASE_URL)),
+            image_base_url: trim_base_url(
+                self.image_base_url.as_deref().unwrap_or(DF_IMAGE_BASE_URL),
+            ),
+            retry: self.retry.clone(),
+            rate_limiter: self.rate_limit.map(RateLimiter::new),
+            concurrency_limiter: self.max_in_flight.map(ConcurrencyLimiter::new),
+            maintenance: self.maintenance.map(Maintenance::new),
+            quota,
+            schema_drift: self.schema_drift.then(SchemaDrift::new),
+            cache: self.cache.clone().map(ResponseCache::new),
+            #[cfg(feature = "disk-cache")]
+            disk_cache: self
+                .disk_cache
+                .clone()
+                .map(|(dir, config)| crate::cache::DiskCache::new(dir, config)),
+            single_flight: self.single_flight.then(SingleFlight::default),
+        }))
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl DfClientBuilder {
+    /// Build [`blocking::DfClient`](crate::blocking::DfClient).
+    ///
+    /// # Errors
+    /// See [`DfClientBuilder::build`].
+    ///
+    /// # Panics
+    /// Panics if the runtime cannot be built.
+    pub fn build_blocking(&self) -> Result<crate::blocking::DfClient> {
+        self.build().map(crate::blocking::DfClient::from_async)
+    }
+}
+
+fn trim_base_url(url: &str) -> String {
+    url.trim_end_matches('/').to_owned()
+}
@@ -0,0 +1,254 @@
+//! Opt-in response cache of [`DfClient`](crate::DfClient).
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::transport::Response;
+
+#[cfg(feature = "disk-cache")]
+mod disk;
+#[cfg(feature = "disk-cache")]
+pub use disk::DiskCache;
+
+/// Group of endpoints sharing a cache TTL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointFamily {
+    /// `/servers/{server}/characters`, `/servers/{server}/characters/{id}`
+    Character,
+    /// `/servers/{server}/characters/{id}/equip/*`, `/servers/{server}/characters/{id}/skill/*`
+    Equipment,
+    /// `/servers/{server}/characters/{id}/timeline`
+    Timeline,
+    /// `/items`, `/items/{id}`, `/multi/items`
+    Item,
+    /// `/auction`, `/auction-sold`
+    Auction,
+    /// Image API
+    Image,
+}
+
+impl EndpointFamily {
+    pub const ALL: [Self; 6] = [
+        Self::Character,
+        Self::Equipment,
+        Self::Timeline,
+        Self::Item,
+        Self::Auction,
+        Self::Image,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Character => "character",
+            Self::Equipment => "equipment",
+            Self::Timeline => "timeline",
+            Self::Item => "item",
+            Self::Auction => "auction",
+            Self::Image => "image",
+        }
+    }
+
+    /// `path` is relative to the API base url.
+    pub(crate) fn of(path: &str) -> Self {
+        let path = path.split('?').next().unwrap_or_default();
+        if path.starts_with("/auction") {
+            Self::Auction
+        } else if path.starts_with("/items") || path.starts_with("/multi/items") {
+            Self::Item
+        } else if path.contains("/timeline") {
+            Self::Timeline
+        } else if path.contains("/equip/") || path.contains("/skill/") {
+            Self::Equipment
+        } else {
+            Self::Character
+        }
+    }
+}
+
+/// TTL of each [`EndpointFamily`]. `None` disables caching of the family.
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// Maximum number of cached responses. The least recently used one is evicted first.
+    pub capacity: usize,
+    pub character: Option<Duration>,
+    pub equipment: Option<Duration>,
+    pub timeline: Option<Duration>,
+    pub item: Option<Duration>,
+    pub auction: Option<Duration>,
+    pub image: Option<Duration>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        const MINUTE: Duration = Duration::from_secs(60);
+        const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+
+        Self {
+            capacity: 1024,
+            character: Some(10 * MINUTE),
+            equipment: Some(MINUTE),
+            timeline: Some(MINUTE),
+            item: Some(DAY),
+            auction: None,
+            image: Some(DAY),
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn ttl(&self, family: EndpointFamily) -> Option<Duration> {
+        match family {
+            EndpointFamily::Character => self.character,
+            EndpointFamily::Equipment => self.equipment,
+            EndpointFamily::Timeline => self.timeline,
+            EndpointFamily::Item => self.item,
+            EndpointFamily::Auction => self.auction,
+            EndpointFamily::Image => self.image,
+        }
+    }
+}
+
+/// In-memory LRU cache of successful responses, keyed by url including query.
+#[derive(Debug)]
+pub struct ResponseCache {
+    config: CacheConfig,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: HashMap<String, Entry>,
+    /// last used tick -> key
+    lru: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    response: Response,
+    family: EndpointFamily,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            state: Default::default(),
+        }
+    }
+
+    pub(crate) fn get(&self, url: &str) -> Option<Response> {
+        let mut state = self.state.lock().unwrap();
+        let entry = state.entries.get(url)?;
+        if entry.expires_at <= Instant::now() {
+            state.remove(url);
+            return None;
+        }
+
+        let response = entry.response.clone();
+        state.touch(url);
+        Some(response)
+    }
+
+    /// `age` is the time since `response` was received, counted against the TTL.
+    pub(crate) fn insert(
+        &self,
+        url: &str,
+        family: EndpointFamily,
+        response: &Response,
+        age: Duration,
+    ) {
+        let Some(ttl) = self.config.ttl(family) else {
+            return;
+        };
+        let ttl = ttl.saturating_sub(age);
+        if ttl.is_zero() || self.config.capacity == 0 {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.remove(url);
+        state.tick += 1;
+        let tick = state.tick;
+        state.lru.insert(tick, url.to_owned());
+        state.entries.insert(
+            url.to_owned(),
+            Entry {
+                response: response.clone(),
+                family,
+                expires_at: Instant::now() + ttl,
+                last_used: tick,
+            },
+        );
+
+        while state.entries.len() > self.config.capacity {
+            let Some((_, oldest)) = state.lru.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+    }
+
+    /// Removes the cached response of `url`.
+    pub fn invalidate(&self, url: &str) {
+        self.state.lock().unwrap().remove(url);
+    }
+
+    /// Removes all cached responses of `family`.
+    pub fn invalidate_family(&self, family: EndpointFamily) {
+        self.invalidate_where(|_, f| f == family);
+    }
+
+    /// Removes cached responses for which `predicate(url, family)` returns `true`.
+    pub fn invalidate_where(&self, predicate: impl Fn(&str, EndpointFamily) -> bool) {
+        let mut state = self.state.lock().unwrap();
+        let keys: Vec<_> = state
+            .entries
+            .iter()
+            .filter(|(url, entry)| predicate(url, entry.family))
+            .map(|(url, _)| url.clone())
+            .collect();
+        keys.iter().for_each(|url| state.remove(url));
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.entries.clear();
+        state.lru.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl State {
+    fn remove(&mut self, url: &str) {
+        if let Some(entry) = self.entries.remove(url) {
+            self.lru.remove(&entry.last_used);
+        }
+    }
+
+    fn touch(&mut self, url: &str) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some(entry) = self.entries.get_mut(url) {
+            self.lru.remove(&entry.last_used);
+            entry.last_used = tick;
+            self.lru.insert(tick, url.to_owned());
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    io,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tracing::warn;
+
+use super::{CacheConfig, EndpointFamily};
+use crate::transport::Response;
+
+/// Persistent cache of raw responses, stored as files under a directory.
+///
+/// Entries survive restarts. Files are replaced atomically,
+/// so several processes on the same host can share the directory.
+///
+/// [`CacheConfig::capacity`] is ignored.
+#[derive(Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+    config: CacheConfig,
+}
+
+/// First line of a cache file. The raw body follows it.
+#[derive(Serialize, Deserialize)]
+struct Metadata {
+    url: String,
+    family: EndpointFamily,
+    /// Unix time in milliseconds.
+    stored_at: u64,
+    status: u16,
+    headers: Vec<(String, String)>,
+}
+
+impl DiskCache {
+    pub fn new(dir: impl Into<PathBuf>, config: CacheConfig) -> Self {
+        Self {
+            dir: dir.into(),
+            config,
+        }
+    }
+
+    /// Returns the response with its age.
+    pub(crate) async fn get(
+        &self,
+        url: &str,
+        family: EndpointFamily,
+    ) -> Option<(Response, Duration)> {
+        let ttl = self.config.ttl(family)?;
+        let path = self.path(url, family);
+        let data = fs::read(&path).await.ok()?;
+
+        let Some((metadata, body)) = decode(&data) else {
+            warn!("Corrupted disk cache: {}", path.display());
+            let _ = fs::remove_file(&path).await;
+            return None;
+        };
+        // hash collision
+        if metadata.url != url {
+            return None;
+        }
+        let age = Duration::from_millis(now_millis().saturating_sub(metadata.stored_at));
+        if age >= ttl {
+            let _ = fs::remove_file(&path).await;
+            return None;
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &metadata.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        let response = Response {
+            status: StatusCode::from_u16(metadata.status).ok()?,
+            headers,
+            body: body.to_vec().into(),
+        };
+        Some((response, age))
+    }
+
+    pub(crate) async fn insert(&self, url: &str, family: EndpointFamily, response: &Response) {
+        if self.config.ttl(family).is_none() {
+            return;
+        }
+        if let Err(e) = self.write(url, family, response).await {
+            warn!("Failed to write disk cache: {e}");
+        }
+    }
+
+    async fn write(
+        &self,
+        url: &str,
+        family: EndpointFamily,
+        response: &Response,
+    ) -> io::Result<()> {
+        let metadata = Metadata {
+            url: url.to_owned(),
+            family,
+            stored_at: now_millis(),
+            status: response.status.as_u16(),
+            headers: response
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_owned()))
+                })
+                .collect(),
+        };
+        let mut data = serde_json::to_vec(&metadata)?;
+        data.push(b'\n');
+        data.extend_from_slice(&response.body);
+
+        let path = self.path(url, family);
+        fs::create_dir_all(self.dir.join(family.name())).await?;
+        // write to a temporary file, then rename it to replace the entry atomically
+        let tmp = path.with_extension(format!(
+            "{}.{:08x}.tmp",
+            std::process::id(),
+            fastrand::u32(..)
+        ));
+        fs::write(&tmp, data).await?;
+        if let Err(e) = fs::rename(&tmp, &path).await {
+            let _ = fs::remove_file(&tmp).await;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Removes the cached response of `url`.
+    pub async fn invalidate(&self, url: &str) {
+        for family in EndpointFamily::ALL {
+            let _ = fs::remove_file(self.path(url, family)).await;
+        }
+    }
+
+    /// Removes all cached responses of `family`.
+    pub async fn invalidate_family(&self, family: EndpointFamily) {
+        let _ = fs::remove_dir_all(self.dir.join(family.name())).await;
+    }
+
+    /// Removes all cached responses.
+    pub async fn clear(&self) {
+        for family in EndpointFamily::ALL {
+            self.invalidate_family(family).await;
+        }
+    }
+
+    /// `{dir}/{family}/{hash of url}`
+    fn path(&self, url: &str, family: EndpointFamily) -> PathBuf {
+        self.dir
+            .join(family.name())
+            .join(format!("{:016x}", fnv1a(url.as_bytes())))
+    }
+}
+
+fn decode(data: &[u8]) -> Option<(Metadata, &[u8])> {
+    let newline = data.iter().position(|&b| b == b'\n')?;
+    let metadata = serde_json::from_slice(&data[..newline]).ok()?;
+    Some((metadata, &data[newline + 1..]))
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Stable across processes and builds, unlike [`std::collections::hash_map::DefaultHasher`].
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
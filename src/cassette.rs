@@ -0,0 +1,255 @@
+//! Record responses to a cassette file, and replay them without network.
+//!
+//! Requests are matched by method, origin, path and query. The API key is never recorded.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), df_rs::Error> {
+//! use df_rs::cassette::CassetteMode;
+//!
+//! let client = df_rs::DfClient::builder()
+//!     .cassette(CassetteMode::Replay("tests/cassettes/items.json".into()))
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use futures::future::BoxFuture;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    StatusCode,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::CassetteError,
+    transport::{Request, Response, Transport},
+    Result,
+};
+
+/// Set by [`DfClientBuilder::cassette`](crate::DfClientBuilder::cassette).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests, and write every response to the file, replacing it.
+    Record(PathBuf),
+    /// Serve responses from the file without sending requests.
+    Replay(PathBuf),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cassette {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+/// Key of matching. `apikey` is removed from the query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    /// Scheme, host and port, as the API and the image hosts share paths.
+    origin: String,
+    path: String,
+    /// Sorted by name.
+    query: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Body,
+}
+
+/// Text as is, e.g. JSON, and bytes otherwise, e.g. images.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Body {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl From<&Request> for RecordedRequest {
+    fn from(request: &Request) -> Self {
+        let mut query: Vec<(String, String)> = request
+            .url
+            .query_pairs()
+            .filter(|(name, _)| name != "apikey")
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        query.sort();
+        Self {
+            method: request.method.to_string(),
+            origin: request.url.origin().ascii_serialization(),
+            path: request.url.path().to_owned(),
+            query,
+        }
+    }
+}
+
+impl From<&Response> for RecordedResponse {
+    fn from(response: &Response) -> Self {
+        let headers = response
+            .headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+            .collect();
+        let body = match std::str::from_utf8(&response.body) {
+            Ok(text) => Body::Text(text.to_owned()),
+            Err(_) => Body::Bytes(response.body.to_vec()),
+        };
+        Self {
+            status: response.status.as_u16(),
+            headers,
+            body,
+        }
+    }
+}
+
+impl From<&RecordedResponse> for Response {
+    fn from(recorded: &RecordedResponse) -> Self {
+        let body = match &recorded.body {
+            Body::Text(text) => text.clone().into_bytes(),
+            Body::Bytes(bytes) => bytes.clone(),
+        };
+        let mut response = Response::new(
+            StatusCode::from_u16(recorded.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            body,
+        );
+        response.headers = recorded
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    HeaderName::try_from(name).ok()?,
+                    HeaderValue::try_from(value).ok()?,
+                ))
+            })
+            .collect::<HeaderMap>();
+        response
+    }
+}
+
+/// [`Transport`] writing every response of `inner` to a cassette file.
+///
+/// The file is rewritten after each response, so it is complete even if the process is killed.
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl RecordingTransport {
+    pub fn new(inner: Arc<dyn Transport>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            cassette: Default::default(),
+        }
+    }
+
+    fn record(&self, request: &Request, response: &Response) -> Result<(), CassetteError> {
+        let mut cassette = self.cassette.lock().unwrap();
+        cassette.interactions.push(Interaction {
+            request: request.into(),
+            response: response.into(),
+        });
+
+        let json = serde_json::to_vec_pretty(&*cassette).map_err(|e| self.json_error(e))?;
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| self.io_error(e))?;
+        }
+        fs::write(&self.path, json).map_err(|e| self.io_error(e))
+    }
+
+    fn io_error(&self, e: std::io::Error) -> CassetteError {
+        CassetteError::Io {
+            path: self.path.clone(),
+            source: Arc::new(e),
+        }
+    }
+
+    fn json_error(&self, e: serde_json::Error) -> CassetteError {
+        CassetteError::Json {
+            path: self.path.clone(),
+            source: Arc::new(e),
+        }
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response>> {
+        Box::pin(async move {
+            let response = self.inner.send(request.clone()).await?;
+            self.record(&request, &response)?;
+            Ok(response)
+        })
+    }
+}
+
+/// [`Transport`] serving responses from a cassette file written by [`RecordingTransport`].
+///
+/// Responses to the same request are served in recorded order, and the last one is repeated.
+pub struct ReplayTransport {
+    interactions: Vec<Interaction>,
+    /// Whether each interaction is served.
+    served: Mutex<Vec<bool>>,
+}
+
+impl ReplayTransport {
+    /// # Errors
+    /// [`CassetteError`] if the file cannot be read or parsed.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CassetteError> {
+        let path = path.as_ref();
+        let json = fs::read(path).map_err(|e| CassetteError::Io {
+            path: path.to_owned(),
+            source: Arc::new(e),
+        })?;
+        let cassette: Cassette =
+            serde_json::from_slice(&json).map_err(|e| CassetteError::Json {
+                path: path.to_owned(),
+                source: Arc::new(e),
+            })?;
+        Ok(Self {
+            served: Mutex::new(vec![false; cassette.interactions.len()]),
+            interactions: cassette.interactions,
+        })
+    }
+
+    fn replay(&self, request: &Request) -> Result<Response, CassetteError> {
+        let key = RecordedRequest::from(request);
+        let matches: Vec<usize> = (0..self.interactions.len())
+            .filter(|&i| self.interactions[i].request == key)
+            .collect();
+
+        let mut served = self.served.lock().unwrap();
+        let index = matches
+            .iter()
+            .copied()
+            .find(|&i| !served[i])
+            .or(matches.last().copied())
+            .ok_or(CassetteError::NoMatch {
+                method: key.method,
+                origin: key.origin,
+                path: key.path,
+                query: key.query,
+            })?;
+        served[index] = true;
+        Ok((&self.interactions[index].response).into())
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response>> {
+        Box::pin(async move { Ok(self.replay(&request)?) })
+    }
+}
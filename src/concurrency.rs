@@ -0,0 +1,102 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::oneshot;
+
+/// Lane of a request waiting for [`DfClientBuilder::max_in_flight`].
+///
+/// Waiting [`Priority::Interactive`] requests are always sent before [`Priority::Background`] ones.
+/// [`Priority::Background`] requests are also refused once the daily quota is used up,
+/// see [`QuotaConfig::refuse_background`].
+///
+/// [`DfClientBuilder::max_in_flight`]: crate::DfClientBuilder::max_in_flight
+/// [`QuotaConfig::refuse_background`]: crate::quota::QuotaConfig::refuse_background
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    #[default]
+    Interactive,
+    Background,
+}
+
+impl Priority {
+    const ALL: [Priority; 2] = [Priority::Interactive, Priority::Background];
+
+    fn lane(self) -> usize {
+        self as usize
+    }
+}
+
+/// Semaphore whose waiters are woken in order of [`Priority`], then FIFO.
+#[derive(Debug, Clone)]
+pub(crate) struct ConcurrencyLimiter {
+    max: usize,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    in_flight: usize,
+    lanes: [VecDeque<oneshot::Sender<Permit>>; Priority::ALL.len()],
+}
+
+/// Releases the slot on drop, handing it over to the next waiter if any.
+#[derive(Debug)]
+pub(crate) struct Permit {
+    /// `None` if the slot is already released.
+    limiter: Option<ConcurrencyLimiter>,
+}
+
+impl ConcurrencyLimiter {
+    /// `max` is at least 1.
+    pub(crate) fn new(max: usize) -> Self {
+        Self {
+            max: max.max(1),
+            state: Default::default(),
+        }
+    }
+
+    pub(crate) async fn acquire(&self, priority: Priority) -> Permit {
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+            if state.in_flight < self.max {
+                state.in_flight += 1;
+                return Permit {
+                    limiter: Some(self.clone()),
+                };
+            }
+            let (sender, receiver) = oneshot::channel();
+            state.lanes[priority.lane()].push_back(sender);
+            receiver
+        };
+
+        // the sender is only dropped with a permit sent
+        receiver.await.expect("permit sender dropped")
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap();
+        for lane in Priority::ALL.map(Priority::lane) {
+            while let Some(sender) = state.lanes[lane].pop_front() {
+                let permit = Permit {
+                    limiter: Some(self.clone()),
+                };
+                match sender.send(permit) {
+                    Ok(()) => return,
+                    // waiter is cancelled, try the next one
+                    Err(mut permit) => permit.limiter = None,
+                }
+            }
+        }
+        state.in_flight -= 1;
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if let Some(limiter) = self.limiter.take() {
+            limiter.release();
+        }
+    }
+}
@@ -1,15 +1,178 @@
-use reqwest::Response;
-use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, fmt, io, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
+use reqwest::header::CONTENT_TYPE;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 use thiserror::Error;
 
-#[derive(Debug, Error)]
-pub enum Error {
+use crate::transport::Response;
+
+/// Error with the request which caused it, if any.
+///
+/// Use [`Error::kind`] to match on the cause.
+///
+/// `Clone` so that a result can be shared by coalesced requests.
+#[derive(Debug, Clone)]
+pub struct Error {
+    kind: ErrorKind,
+    context: Option<Box<RequestContext>>,
+}
+
+/// Cause of an [`Error`](struct@Error).
+#[derive(Debug, Error, Clone)]
+pub enum ErrorKind {
     #[error("{0}")]
-    Reqwest(#[from] reqwest::Error),
+    Reqwest(Arc<reqwest::Error>),
     #[error("{0}")]
     Response(#[from] ResponseError),
     #[error("{0}")]
     InvalidQueryParameter(#[from] InvalidQueryParameter),
+    #[error("{0}")]
+    Decode(#[from] DecodeError),
+    #[error("{0}")]
+    UnexpectedResponse(#[from] UnexpectedResponse),
+    /// API key is not a valid header value.
+    #[error("Invalid API key")]
+    InvalidApiKey,
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+    #[error("{0}")]
+    SerializeQuery(#[from] serde_urlencoded::ser::Error),
+    /// Request is short-circuited while the API is under maintenance.
+    ///
+    /// See [`DfClientBuilder::maintenance`](crate::DfClientBuilder::maintenance).
+    #[error("API is under maintenance (retry after {retry_after:?})")]
+    Maintenance { retry_after: Duration },
+    /// Handler call is not completed within the timeout or the deadline.
+    ///
+    /// See [`DfClient::with_timeout`](crate::DfClient::with_timeout).
+    #[error("Request timed out after {elapsed:?}")]
+    Timeout { elapsed: Duration },
+    /// See [`DfClient::with_cancellation`](crate::DfClient::with_cancellation).
+    #[error("Request cancelled")]
+    Cancelled,
+    /// Background request is refused because every API key has used the daily budget.
+    ///
+    /// See [`QuotaConfig::refuse_background`](crate::quota::QuotaConfig::refuse_background).
+    #[error("Daily quota budget is exhausted ({used}/{budget}, resets in {resets_in:?})")]
+    QuotaExhausted {
+        used: u64,
+        budget: u64,
+        resets_in: Duration,
+    },
+    #[error("{0}")]
+    Cassette(#[from] CassetteError),
+}
+
+impl From<reqwest::Error> for ErrorKind {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Reqwest(Arc::new(e))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.context {
+            Some(context) => write!(f, "{} ({context})", self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.kind.source()
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self {
+            kind,
+            context: None,
+        }
+    }
+}
+
+macro_rules! impl_from_for_error {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl From<$t> for Error {
+                fn from(e: $t) -> Self {
+                    ErrorKind::from(e).into()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_error!(
+    reqwest::Error,
+    ResponseError,
+    InvalidQueryParameter,
+    DecodeError,
+    UnexpectedResponse,
+    url::ParseError,
+    serde_urlencoded::ser::Error,
+    CassetteError,
+);
+
+/// Request which caused an [`Error`](struct@Error).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestContext {
+    /// Url template, e.g. `/servers/{server}/characters`.
+    ///
+    /// Relative to the API base url, or prefixed with `image:` for the image API,
+    /// e.g. `image:/items/{id}`, as both share paths.
+    /// Also used as `endpoint` field of tracing spans and label of metrics.
+    pub endpoint: String,
+    /// Requested url without the API key.
+    pub url: String,
+    /// Query parameters without the API key.
+    pub query: Vec<(String, String)>,
+    /// Number of attempts sent, `0` if the error occurred before sending.
+    pub attempt: u32,
+    /// Elapsed time since the first attempt.
+    pub elapsed: Duration,
+}
+
+impl RequestContext {
+    pub(crate) fn new(endpoint: &str, url: &str) -> Self {
+        let (url, query) = match url::Url::parse(url) {
+            Ok(mut parsed) => {
+                let query: Vec<(String, String)> = parsed
+                    .query_pairs()
+                    .filter(|(k, _)| k != "apikey")
+                    .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                    .collect();
+                if query.is_empty() {
+                    parsed.set_query(None);
+                } else {
+                    parsed.query_pairs_mut().clear().extend_pairs(&query);
+                }
+                (parsed.to_string(), query)
+            }
+            Err(_) => (url.to_owned(), Vec::new()),
+        };
+
+        Self {
+            endpoint: endpoint.to_owned(),
+            url,
+            query,
+            attempt: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl fmt::Display for RequestContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "endpoint: {}, url: {}, attempt: {}, elapsed: {:?}",
+            self.endpoint, self.url, self.attempt, self.elapsed
+        )
+    }
 }
 
 #[derive(Debug, Error, Clone, Deserialize)]
@@ -27,8 +190,94 @@ pub struct InvalidQueryParameter {
     pub message: String,
 }
 
+/// Response body doesn't match the model.
+#[derive(Debug, Error, Clone)]
+#[error("Failed to decode response of {endpoint} at `{path}`: {message}")]
+pub struct DecodeError {
+    /// Path of the request, e.g. `/servers/cain/characters`.
+    pub endpoint: String,
+    /// JSON path where decoding failed, e.g. `rows[0].level`.
+    pub path: String,
+    pub message: String,
+    /// Beginning of the body.
+    pub snippet: String,
+}
+
+/// Failure of recording or replaying a [cassette](crate::cassette).
+#[derive(Debug, Error, Clone)]
+pub enum CassetteError {
+    #[error("Failed to access cassette {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: Arc<io::Error>,
+    },
+    #[error("Invalid cassette {}: {source}", path.display())]
+    Json {
+        path: PathBuf,
+        source: Arc<serde_json::Error>,
+    },
+    /// Apart from `apikey`, the request is not recorded.
+    #[error("No recorded response for {method} {origin}{path} {query:?}")]
+    NoMatch {
+        method: String,
+        origin: String,
+        path: String,
+        query: Vec<(String, String)>,
+    },
+}
+
+/// Non-2xx response whose body is not a Neople API error,
+/// e.g. HTML error page of a proxy or gateway.
+#[derive(Debug, Error, Clone)]
+#[error("Unexpected response of {endpoint}: status: {status}, content-type: {}, body: {excerpt}", content_type.as_deref().unwrap_or("-"))]
+pub struct UnexpectedResponse {
+    /// Path of the request, e.g. `/servers/cain/characters`.
+    pub endpoint: String,
+    pub status: u16,
+    pub content_type: Option<String>,
+    /// Beginning of the body.
+    pub excerpt: String,
+}
+
+impl DecodeError {
+    const SNIPPET_LEN: usize = 256;
+
+    pub(crate) fn decode<T: DeserializeOwned>(endpoint: &str, body: &[u8]) -> Result<T, Self> {
+        let deserializer = &mut serde_json::Deserializer::from_slice(body);
+        serde_path_to_error::deserialize(deserializer)
+            .map_err(|e| Self::from_path_error(endpoint, body, e))
+    }
+
+    pub(crate) fn from_path_error(
+        endpoint: &str,
+        body: &[u8],
+        e: serde_path_to_error::Error<serde_json::Error>,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.to_owned(),
+            path: e.path().to_string(),
+            message: e.into_inner().to_string(),
+            snippet: snippet(body, Self::SNIPPET_LEN),
+        }
+    }
+}
+
+/// Lossy UTF-8 of at most `len` bytes of `body`.
+pub(crate) fn snippet(body: &[u8], len: usize) -> String {
+    let mut snippet = String::from_utf8_lossy(&body[..body.len().min(len)]).into_owned();
+    if body.len() > len {
+        snippet.push_str("...");
+    }
+    snippet
+}
+
 impl ResponseError {
-    pub(crate) async fn from_response(response: Response) -> Self {
+    /// # Errors
+    /// [`UnexpectedResponse`] if the body is not a Neople API error.
+    pub(crate) fn from_response(
+        endpoint: &str,
+        response: &Response,
+    ) -> Result<Self, UnexpectedResponse> {
         #[derive(Deserialize)]
         struct OuterError {
             error: ResponseError,
@@ -36,11 +285,23 @@ impl ResponseError {
 
         // origin: { "error": { "status": 404, ... } }
 
-        response.json::<OuterError>().await.unwrap().error
+        response
+            .json::<OuterError>()
+            .map(|outer| outer.error)
+            .map_err(|_| UnexpectedResponse {
+                endpoint: endpoint.to_owned(),
+                status: response.status.as_u16(),
+                content_type: response
+                    .headers
+                    .get(CONTENT_TYPE)
+                    .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned()),
+                excerpt: snippet(&response.body, DecodeError::SNIPPET_LEN),
+            })
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+/// Unknown codes are deserialized as [`ErrorCode::Unknown`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, DeserializeFromStr, SerializeDisplay)]
 pub enum ErrorCode {
     /// API Key 미입력
     API000,
@@ -113,6 +374,9 @@ pub enum ErrorCode {
 
     /// 시스템 오류
     DNF999,
+
+    /// 알 수 없는 오류 코드
+    Unknown(String),
 }
 
 impl std::fmt::Display for ErrorCode {
@@ -142,6 +406,7 @@ impl std::fmt::Display for ErrorCode {
             Self::DNF901 => write!(f, "DNF901"),
             Self::DNF980 => write!(f, "DNF980"),
             Self::DNF999 => write!(f, "DNF999"),
+            Self::Unknown(code) => write!(f, "{code}"),
         }
     }
 }
@@ -173,6 +438,176 @@ impl ErrorCode {
             Self::DNF901 => "유효하지 않은 요청 파라미터",
             Self::DNF980 => "시스템 점검",
             Self::DNF999 => "시스템 오류",
+            Self::Unknown(_) => "알 수 없는 오류 코드",
         }
     }
 }
+
+impl FromStr for ErrorCode {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "API000" => Self::API000,
+            "API001" => Self::API001,
+            "API002" => Self::API002,
+            "API003" => Self::API003,
+            "API004" => Self::API004,
+            "API005" => Self::API005,
+            "API006" => Self::API006,
+            "API007" => Self::API007,
+            "API900" => Self::API900,
+            "API901" => Self::API901,
+            "API999" => Self::API999,
+            "DNF000" => Self::DNF000,
+            "DNF001" => Self::DNF001,
+            "DNF003" => Self::DNF003,
+            "DNF004" => Self::DNF004,
+            "DNF005" => Self::DNF005,
+            "DNF006" => Self::DNF006,
+            "DNF007" => Self::DNF007,
+            "DNF008" => Self::DNF008,
+            "DNF009" => Self::DNF009,
+            "DNF900" => Self::DNF900,
+            "DNF901" => Self::DNF901,
+            "DNF980" => Self::DNF980,
+            "DNF999" => Self::DNF999,
+            _ => Self::Unknown(s.to_owned()),
+        })
+    }
+}
+
+/// # Classification
+impl ErrorCode {
+    /// Transient errors worth retrying:
+    /// [`API002`](Self::API002), [`API007`](Self::API007), [`API999`](Self::API999), [`DNF999`](Self::DNF999)
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::API002 | Self::API007 | Self::API999 | Self::DNF999
+        )
+    }
+
+    /// [`API002`](Self::API002)
+    pub fn is_quota_exceeded(&self) -> bool {
+        matches!(self, Self::API002)
+    }
+
+    /// [`DNF980`](Self::DNF980)
+    pub fn is_maintenance(&self) -> bool {
+        matches!(self, Self::DNF980)
+    }
+
+    /// [`DNF001`](Self::DNF001), [`DNF003`](Self::DNF003), [`DNF004`](Self::DNF004), [`DNF005`](Self::DNF005)
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Self::DNF001 | Self::DNF003 | Self::DNF004 | Self::DNF005
+        )
+    }
+
+    /// Missing, invalid or blocked API key:
+    /// [`API000`](Self::API000), [`API003`](Self::API003), [`API004`](Self::API004), [`API005`](Self::API005)
+    pub fn is_auth_problem(&self) -> bool {
+        matches!(
+            self,
+            Self::API000 | Self::API003 | Self::API004 | Self::API005
+        )
+    }
+}
+
+/// # Classification
+impl ResponseError {
+    pub fn is_retryable(&self) -> bool {
+        self.code.is_retryable()
+    }
+
+    pub fn is_quota_exceeded(&self) -> bool {
+        self.code.is_quota_exceeded()
+    }
+
+    pub fn is_maintenance(&self) -> bool {
+        self.code.is_maintenance()
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.code.is_not_found()
+    }
+
+    pub fn is_auth_problem(&self) -> bool {
+        self.code.is_auth_problem()
+    }
+}
+
+/// # Context
+impl Error {
+    /// Attaches `context`, unless already attached.
+    pub(crate) fn with_context(mut self, context: RequestContext) -> Self {
+        self.context.get_or_insert_with(|| Box::new(context));
+        self
+    }
+
+    /// Request which caused this error, if any.
+    ///
+    /// Attached to every error returned by [`DfClient`](crate::DfClient).
+    pub fn context(&self) -> Option<&RequestContext> {
+        self.context.as_deref()
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn into_kind(self) -> ErrorKind {
+        self.kind
+    }
+}
+
+/// # Classification
+impl Error {
+    /// Error code of Neople API, if any.
+    pub fn code(&self) -> Option<&ErrorCode> {
+        match self.kind() {
+            ErrorKind::Response(e) => Some(&e.code),
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the response, if any.
+    pub fn status(&self) -> Option<u16> {
+        match self.kind() {
+            ErrorKind::Reqwest(e) => e.status().map(|status| status.as_u16()),
+            ErrorKind::Response(e) => Some(e.status),
+            ErrorKind::UnexpectedResponse(e) => Some(e.status),
+            _ => None,
+        }
+    }
+
+    /// [`ErrorCode::is_retryable`], connection errors, timeouts and gateway errors (502, 503, 504).
+    pub fn is_retryable(&self) -> bool {
+        match self.kind() {
+            ErrorKind::Reqwest(e) => e.is_connect() || e.is_timeout(),
+            ErrorKind::Response(e) => e.is_retryable(),
+            ErrorKind::UnexpectedResponse(e) => matches!(e.status, 502..=504),
+            _ => false,
+        }
+    }
+
+    pub fn is_quota_exceeded(&self) -> bool {
+        self.code().is_some_and(ErrorCode::is_quota_exceeded)
+    }
+
+    /// `DNF980`, or [`ErrorKind::Maintenance`].
+    pub fn is_maintenance(&self) -> bool {
+        matches!(self.kind(), ErrorKind::Maintenance { .. })
+            || self.code().is_some_and(ErrorCode::is_maintenance)
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.code().is_some_and(ErrorCode::is_not_found)
+    }
+
+    pub fn is_auth_problem(&self) -> bool {
+        self.code().is_some_and(ErrorCode::is_auth_problem)
+    }
+}
@@ -0,0 +1,86 @@
+//! Global registry of [`DfClient`].
+//!
+//! Instances can be replaced at any time, e.g. to rotate a compromised API key.
+//! Clones obtained before replacing keep the old configuration,
+//! so call [`instance`] when needed instead of holding a clone.
+
+use std::{collections::BTreeMap, sync::RwLock};
+
+use crate::{DfClient, DfClientBuilder, Result};
+
+/// Name of the instance used by [`instance`], [`try_instance`] and [`initialize`].
+pub const DEFAULT_INSTANCE: &str = "default";
+
+static REGISTRY: RwLock<BTreeMap<String, DfClient>> = RwLock::new(BTreeMap::new());
+
+/// Get global instance.
+///
+/// # Panics
+/// Panics if global instance is not initialized. Use [`try_instance`] instead.
+pub fn instance() -> DfClient {
+    try_instance().expect("DfClient is not initialized")
+}
+
+/// Get global instance, `None` if not initialized.
+pub fn try_instance() -> Option<DfClient> {
+    named_instance(DEFAULT_INSTANCE)
+}
+
+/// Initializes global [`DfClient`] instance, replacing the previous one.
+///
+/// # Panics
+/// Panics if `api_key` is not a valid header value. Use [`configure`] to handle the error.
+pub fn initialize(api_key: &str) -> DfClient {
+    set_instance(DfClient::new(api_key))
+}
+
+/// Builds global [`DfClient`] instance from `builder`, replacing the previous one.
+///
+/// # Errors
+/// See [`DfClientBuilder::build`]. The previous instance is kept on error.
+pub fn configure(builder: &DfClientBuilder) -> Result<DfClient> {
+    configure_named(DEFAULT_INSTANCE, builder)
+}
+
+/// Sets global instance, replacing the previous one.
+pub fn set_instance(client: DfClient) -> DfClient {
+    set_named_instance(DEFAULT_INSTANCE, client)
+}
+
+/// Get instance registered as `name`.
+pub fn named_instance(name: &str) -> Option<DfClient> {
+    registry_read().get(name).cloned()
+}
+
+/// Builds instance from `builder` and registers it as `name`, replacing the previous one.
+///
+/// # Errors
+/// See [`DfClientBuilder::build`]. The previous instance is kept on error.
+pub fn configure_named(name: &str, builder: &DfClientBuilder) -> Result<DfClient> {
+    Ok(set_named_instance(name, builder.build()?))
+}
+
+/// Registers `client` as `name`, replacing the previous one.
+pub fn set_named_instance(name: &str, client: DfClient) -> DfClient {
+    registry_write().insert(name.to_owned(), client.clone());
+    client
+}
+
+/// Unregisters instance of `name`.
+pub fn remove_named_instance(name: &str) -> Option<DfClient> {
+    registry_write().remove(name)
+}
+
+/// Names of registered instances.
+pub fn instance_names() -> Vec<String> {
+    registry_read().keys().cloned().collect()
+}
+
+// a panic while holding the lock cannot leave the map inconsistent
+fn registry_read() -> std::sync::RwLockReadGuard<'static, BTreeMap<String, DfClient>> {
+    REGISTRY.read().unwrap_or_else(|e| e.into_inner())
+}
+
+fn registry_write() -> std::sync::RwLockWriteGuard<'static, BTreeMap<String, DfClient>> {
+    REGISTRY.write().unwrap_or_else(|e| e.into_inner())
+}
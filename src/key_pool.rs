@@ -0,0 +1,193 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use reqwest::header::HeaderValue;
+use tokio::time::Instant;
+use tracing::warn;
+use zeroize::Zeroizing;
+
+use crate::{
+    error::{ErrorCode, ErrorKind},
+    Result,
+};
+
+/// How [`DfClient`](crate::DfClient) picks an API key for each request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeySelection {
+    #[default]
+    RoundRobin,
+    /// The key with the fewest requests so far.
+    LeastUsed,
+}
+
+/// Usage counters of an API key.
+#[derive(Debug, Clone)]
+pub struct KeyUsage {
+    /// Position in the keys given to [`DfClientBuilder::api_keys`](crate::DfClientBuilder::api_keys).
+    pub index: usize,
+    /// Masked key, e.g. `abcd****`.
+    pub key: String,
+    pub requests: u64,
+    pub errors: u64,
+    /// Remaining cool-down after [`ErrorCode::API002`], [`ErrorCode::API003`] or [`ErrorCode::API004`].
+    pub benched_for: Option<Duration>,
+}
+
+/// API keys of a client. Keys failing with quota or auth errors are benched for a cool-down period.
+#[derive(Debug)]
+pub(crate) struct KeyPool {
+    keys: Vec<Key>,
+    selection: KeySelection,
+    cooldown: Duration,
+    next: AtomicUsize,
+}
+
+/// `Debug` shows the masked key only.
+struct Key {
+    /// Zeroed on drop. Validated as a header value.
+    secret: Zeroizing<String>,
+    masked: String,
+    requests: AtomicU64,
+    errors: AtomicU64,
+    benched_until: Mutex<Option<Instant>>,
+}
+
+impl KeyPool {
+    /// # Errors
+    /// [`ErrorKind::InvalidApiKey`] if any key is not a valid header value.
+    pub(crate) fn new(
+        keys: &[Zeroizing<String>],
+        selection: KeySelection,
+        cooldown: Duration,
+    ) -> Result<Self> {
+        let keys = keys
+            .iter()
+            .map(|key| {
+                HeaderValue::from_str(key).map_err(|_| ErrorKind::InvalidApiKey)?;
+                Ok(Key {
+                    secret: key.clone(),
+                    masked: mask(key),
+                    requests: AtomicU64::new(0),
+                    errors: AtomicU64::new(0),
+                    benched_until: Mutex::new(None),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        assert!(!keys.is_empty(), "at least one API key is required");
+
+        Ok(Self {
+            keys,
+            selection,
+            cooldown,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// Picks a key and counts a request on it.
+    ///
+    /// If every key is benched, the one available soonest is picked.
+    /// The header value is marked as sensitive, so that `Debug` doesn't show it.
+    pub(crate) fn select(&self) -> (usize, HeaderValue) {
+        let now = Instant::now();
+        let available: Vec<usize> = (0..self.keys.len())
+            .filter(|&i| !self.keys[i].is_benched(now))
+            .collect();
+
+        let index = if available.is_empty() {
+            warn!("All API keys are benched");
+            (0..self.keys.len())
+                .min_by_key(|&i| *self.keys[i].benched_until.lock().unwrap())
+                .unwrap()
+        } else {
+            match self.selection {
+                KeySelection::RoundRobin => {
+                    available[self.next.fetch_add(1, Ordering::Relaxed) % available.len()]
+                }
+                KeySelection::LeastUsed => available
+                    .into_iter()
+                    .min_by_key(|&i| self.keys[i].requests.load(Ordering::Relaxed))
+                    .unwrap(),
+            }
+        };
+
+        let key = &self.keys[index];
+        key.requests.fetch_add(1, Ordering::Relaxed);
+        let mut value = HeaderValue::from_str(&key.secret).expect("validated by KeyPool::new");
+        value.set_sensitive(true);
+        (index, value)
+    }
+
+    /// Records the result of a request made with the key at `index`.
+    pub(crate) fn report<T>(&self, index: usize, result: &Result<T>) {
+        let Err(error) = result else {
+            return;
+        };
+        let key = &self.keys[index];
+        key.errors.fetch_add(1, Ordering::Relaxed);
+
+        if let ErrorKind::Response(e) = error.kind() {
+            if matches!(
+                e.code,
+                ErrorCode::API002 | ErrorCode::API003 | ErrorCode::API004
+            ) {
+                warn!(
+                    "Bench API key {} for {:?}: {}",
+                    key.masked, self.cooldown, e.code
+                );
+                *key.benched_until.lock().unwrap() = Some(Instant::now() + self.cooldown);
+            }
+        }
+    }
+
+    pub(crate) fn usage(&self) -> Vec<KeyUsage> {
+        let now = Instant::now();
+        self.keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| KeyUsage {
+                index,
+                key: key.masked.clone(),
+                requests: key.requests.load(Ordering::Relaxed),
+                errors: key.errors.load(Ordering::Relaxed),
+                benched_for: key
+                    .benched_until
+                    .lock()
+                    .unwrap()
+                    .filter(|until| *until > now)
+                    .map(|until| until - now),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Key")
+            .field("masked", &self.masked)
+            .field("requests", &self.requests)
+            .field("errors", &self.errors)
+            .field("benched_until", &self.benched_until)
+            .finish()
+    }
+}
+
+impl Key {
+    fn is_benched(&self, now: Instant) -> bool {
+        self.benched_until
+            .lock()
+            .unwrap()
+            .is_some_and(|until| until > now)
+    }
+}
+
+/// `abcdefgh` -> `abcd****`
+pub(crate) fn mask(key: &str) -> String {
+    let visible: String = key.chars().take(4.min(key.chars().count() / 2)).collect();
+    format!("{visible}****")
+}
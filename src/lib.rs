@@ -1,59 +1,233 @@
 pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod builder;
+pub mod cache;
+pub mod cassette;
+pub mod concurrency;
+pub use builder::DfClientBuilder;
 pub mod error;
-pub use error::Error;
-use error::ResponseError;
-use serde::Serialize;
-use tracing::{error, info};
+pub mod global;
+pub use global::{configure, initialize, instance, try_instance};
+pub mod key_pool;
+pub mod maintenance;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod middleware;
+#[cfg(feature = "mock")]
+pub mod mock;
+use error::{DecodeError, InvalidQueryParameter, RequestContext, ResponseError};
+pub use error::{Error, ErrorKind};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{debug, error, field, info, info_span, warn, Instrument, Span};
 pub mod model;
+pub mod quota;
+pub mod rate_limit;
+pub mod retry;
+pub mod schema_drift;
+mod single_flight;
+pub mod transport;
 pub mod util;
 
-use std::sync::OnceLock;
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use api::{
     auction::AuctionHandler, character::CharacterHandler, image::ImageHandler, item::ItemHandler,
 };
-use reqwest::Response;
+use cache::{EndpointFamily, ResponseCache};
+use concurrency::{ConcurrencyLimiter, Priority};
+use key_pool::{KeyPool, KeyUsage};
+use maintenance::{Maintenance, MaintenanceState};
+use middleware::Middlewares;
+use quota::{Quota, QuotaUsage};
+use rate_limit::RateLimiter;
+use reqwest::{header::HeaderMap, Method, Url};
+use retry::{RetryEvent, RetryPolicy};
+use schema_drift::SchemaDrift;
+use single_flight::SingleFlight;
+use tokio::{sync::watch, time::Instant};
+use transport::{Request, Response, Transport};
+
+pub use tokio_util::sync::CancellationToken;
 
 type Result<T, E = Error> = std::result::Result<T, E>;
 
 const DF_BASE_URL: &str = "https://api.neople.co.kr/df";
+const DF_IMAGE_BASE_URL: &str = "https://img-api.neople.co.kr/df";
 
-static STATIC_INSTACE: OnceLock<DfClient> = OnceLock::new();
+/// Client of [Dungeon & Fighter API](https://developers.neople.co.kr/contents/apiDocs/df).
+#[derive(Clone)]
+pub struct DfClient {
+    inner: Arc<ClientInner>,
+    options: RequestOptions,
+}
 
-/// Get global instance.
-///
-/// # Panics
-/// Panics if global instance is not initialized.
-pub fn instance() -> DfClient {
-    STATIC_INSTACE
-        .get()
-        .expect("DfClient is not initialized")
-        .clone()
+/// Options of requests sent by a clone of [`DfClient`].
+#[derive(Debug, Clone, Default)]
+struct RequestOptions {
+    priority: Priority,
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    cancellation: Option<CancellationToken>,
 }
 
-/// Initializes global [`DfClient`] instance.
-pub fn initialize(api_key: &str) -> DfClient {
-    STATIC_INSTACE
-        .get_or_init(|| DfClient::new(api_key))
-        .clone()
+impl RequestOptions {
+    /// Runs `future` until the timeout, the deadline or the cancellation, whichever comes first.
+    async fn bound<T>(&self, start: Instant, future: impl Future<Output = Result<T>>) -> Result<T> {
+        let deadline = match (self.timeout.map(|timeout| start + timeout), self.deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let timeout = async {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        let cancelled = async {
+            match &self.cancellation {
+                Some(token) => token.cancelled().await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            biased;
+            _ = cancelled => Err(ErrorKind::Cancelled.into()),
+            _ = timeout => Err(ErrorKind::Timeout {
+                elapsed: start.elapsed(),
+            }
+            .into()),
+            result = future => result,
+        }
+    }
 }
 
-/// Client of [Dungeon & Fighter API](https://developers.neople.co.kr/contents/apiDocs/df).
-#[derive(Clone, Default)]
-pub struct DfClient {
-    inner: reqwest::Client,
+struct ClientInner {
+    transport: Arc<dyn Transport>,
+    middlewares: Middlewares,
+    keys: KeyPool,
+    base_url: String,
+    image_base_url: String,
+    retry: Option<RetryPolicy>,
+    rate_limiter: Option<RateLimiter>,
+    concurrency_limiter: Option<ConcurrencyLimiter>,
+    maintenance: Option<Maintenance>,
+    quota: Option<Quota>,
+    cache: Option<ResponseCache>,
+    #[cfg(feature = "disk-cache")]
+    disk_cache: Option<cache::DiskCache>,
+    schema_drift: Option<SchemaDrift>,
+    /// `None` if disabled by [`DfClientBuilder::single_flight`].
+    single_flight: Option<SingleFlight>,
 }
 
 /// # Constructor
 impl DfClient {
+    /// # Panics
+    /// Panics if `api_key` is not a valid header value,
+    /// or the underlying [`reqwest::Client`] cannot be built.
+    ///
+    /// Use [`DfClient::builder`] to handle these errors.
     pub fn new(api_key: &str) -> Self {
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert("apikey", api_key.parse().unwrap());
-        let client = reqwest::Client::builder()
-            .default_headers(headers)
+        Self::builder()
+            .api_key(api_key)
             .build()
-            .unwrap();
-        Self { inner: client }
+            .expect("failed to build DfClient")
+    }
+
+    pub fn builder() -> DfClientBuilder {
+        DfClientBuilder::new()
+    }
+
+    fn from_inner(inner: ClientInner) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            options: Default::default(),
+        }
+    }
+}
+
+/// # Request Options
+impl DfClient {
+    /// Clone sending requests with `priority`. Default: [`Priority::Interactive`]
+    ///
+    /// Handlers created from the clone inherit the priority.
+    /// Only effective with [`DfClientBuilder::max_in_flight`]
+    /// and [`QuotaConfig::refuse_background`](quota::QuotaConfig::refuse_background).
+    ///
+    /// ```no_run
+    /// # use df_rs::{concurrency::Priority, DfClient};
+    /// # let client = DfClient::new("<YOUR_API_KEY>");
+    /// let crawler = client.with_priority(Priority::Background);
+    /// let mut handler = crawler.item();
+    /// ```
+    pub fn with_priority(&self, priority: Priority) -> Self {
+        let mut client = self.clone();
+        client.options.priority = priority;
+        client
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.options.priority
+    }
+
+    /// Clone whose each handler call fails with [`ErrorKind::Timeout`] after `timeout`,
+    /// including retries and waiting for the rate limit.
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        let mut client = self.clone();
+        client.options.timeout = Some(timeout);
+        client
+    }
+
+    /// Clone whose handler calls fail with [`ErrorKind::Timeout`] after `deadline`.
+    ///
+    /// Useful to bound a whole batch, unlike [`DfClient::with_timeout`].
+    pub fn with_deadline(&self, deadline: Instant) -> Self {
+        let mut client = self.clone();
+        client.options.deadline = Some(deadline);
+        client
+    }
+
+    /// Clone whose handler calls fail with [`ErrorKind::Cancelled`] once `token` is cancelled.
+    ///
+    /// ```no_run
+    /// # use df_rs::{CancellationToken, DfClient};
+    /// # let client = DfClient::new("<YOUR_API_KEY>");
+    /// let token = CancellationToken::new();
+    /// let batch = client.with_cancellation(token.clone());
+    /// // token.cancel() from elsewhere stops every request of `batch`
+    /// ```
+    pub fn with_cancellation(&self, token: CancellationToken) -> Self {
+        let mut client = self.clone();
+        client.options.cancellation = Some(token);
+        client
+    }
+}
+
+/// API keys are masked.
+impl fmt::Debug for DfClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DfClient")
+            .field("keys", &self.inner.keys)
+            .field("base_url", &self.inner.base_url)
+            .field("image_base_url", &self.inner.image_base_url)
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for DfClient {
+    fn default() -> Self {
+        Self::builder().build().expect("failed to build DfClient")
     }
 }
 
@@ -76,35 +250,483 @@ impl DfClient {
     }
 }
 
+/// # Send Request
+///
+/// `endpoint` is the url template, e.g. `/servers/{server}/characters`,
+/// attached to errors with [`RequestContext`].
 impl DfClient {
-    async fn get(&self, url: &str) -> Result<Response> {
-        self.get_with_query::<()>(url, None).await
+    async fn get(&self, endpoint: &str, url: &str) -> Result<Response> {
+        self.get_with_query::<()>(endpoint, url, None).await
     }
 
-    async fn get_with_query<T>(&self, url: &str, query: Option<&T>) -> Result<Response>
+    async fn get_with_query<T>(
+        &self,
+        endpoint: &str,
+        url: &str,
+        query: Option<&T>,
+    ) -> Result<Response>
     where
         T: Serialize + ?Sized,
     {
-        let url = if url.starts_with("https://") {
+        self.call(endpoint, url, query, |_, response| Ok(response))
+            .await
+    }
+
+    async fn get_json<T>(&self, endpoint: &str, url: &str) -> Result<T>
+    where
+        T: DeserializeOwned + Serialize,
+    {
+        self.get_json_with_query::<T, ()>(endpoint, url, None).await
+    }
+
+    async fn get_json_with_query<T, Q>(
+        &self,
+        endpoint: &str,
+        url: &str,
+        query: Option<&Q>,
+    ) -> Result<T>
+    where
+        T: DeserializeOwned + Serialize,
+        Q: Serialize + ?Sized,
+    {
+        self.call(endpoint, url, query, |path, response| {
+            Ok(match &self.inner.schema_drift {
+                Some(schema_drift) => schema_drift.decode(endpoint, path, &response.body)?,
+                None => DecodeError::decode(path, &response.body)?,
+            })
+        })
+        .await
+    }
+
+    /// Sends request and parses the response, attaching [`RequestContext`] to any error.
+    ///
+    /// Runs in `df_request` span with fields `endpoint`, `server`, `status`, `code`,
+    /// `retries` and `latency_ms`.
+    async fn call<Q, T, F>(
+        &self,
+        endpoint: &str,
+        url: &str,
+        query: Option<&Q>,
+        parse: F,
+    ) -> Result<T>
+    where
+        Q: Serialize + ?Sized,
+        F: FnOnce(&str, Response) -> Result<T>,
+    {
+        let span = info_span!(
+            "df_request",
+            endpoint,
+            server = field::Empty,
+            status = field::Empty,
+            code = field::Empty,
+            retries = field::Empty,
+            latency_ms = field::Empty,
+        );
+        if let Some(server) = server_of(url) {
+            span.record("server", server);
+        }
+
+        let start = Instant::now();
+        let result = self
+            .call_in_span(start, endpoint, url, query, parse)
+            .instrument(span.clone())
+            .await;
+        let latency = start.elapsed();
+
+        span.record("latency_ms", latency.as_millis() as u64);
+        if let Err(e) = &result {
+            if let Some(status) = e.status() {
+                span.record("status", status);
+            }
+            if let Some(code) = e.code() {
+                span.record("code", field::display(code));
+            }
+        }
+        #[cfg(feature = "metrics")]
+        metrics::record(endpoint, &result, latency);
+        result
+    }
+
+    async fn call_in_span<Q, T, F>(
+        &self,
+        start: Instant,
+        endpoint: &str,
+        url: &str,
+        query: Option<&Q>,
+        parse: F,
+    ) -> Result<T>
+    where
+        Q: Serialize + ?Sized,
+        F: FnOnce(&str, Response) -> Result<T>,
+    {
+        let (request, family) = match self.build_request(url, query) {
+            Ok(request) => request,
+            Err(e) => {
+                let context = RequestContext::new(endpoint, &self.absolute_url(url));
+                return Err(e.with_context(context));
+            }
+        };
+
+        let mut context = RequestContext::new(endpoint, request.url.as_str());
+        let path = request.url.path().to_owned();
+        let mut attempts = Arc::default();
+        let result = match self
+            .options
+            .bound(
+                start,
+                self.execute(&context, request, family, &mut attempts),
+            )
+            .await
+        {
+            Ok(response) => {
+                Span::current().record("status", response.status.as_u16());
+                parse(&path, response)
+            }
+            Err(e) => Err(e),
+        };
+        result.map_err(|e| {
+            context.attempt = attempts.load(Ordering::Relaxed);
+            context.elapsed = start.elapsed();
+            e.with_context(context)
+        })
+    }
+
+    /// Attaches [`RequestContext`] to an error of parameter validation.
+    pub(crate) fn invalid_query(&self, endpoint: &str, error: InvalidQueryParameter) -> Error {
+        let context = RequestContext::new(endpoint, &self.absolute_url(&error.path));
+        Error::from(error).with_context(context)
+    }
+
+    fn absolute_url(&self, url: &str) -> String {
+        if url.starts_with("https://") || url.starts_with("http://") {
             url.to_owned()
         } else {
-            format!("{}{}", DF_BASE_URL, url)
+            format!("{}{}", self.inner.base_url, url)
+        }
+    }
+
+    fn build_request<T>(&self, url: &str, query: Option<&T>) -> Result<(Request, EndpointFamily)>
+    where
+        T: Serialize + ?Sized,
+    {
+        let family = if url.starts_with("https://") || url.starts_with("http://") {
+            EndpointFamily::Image
+        } else {
+            EndpointFamily::of(url)
+        };
+        let mut url = Url::parse(&self.absolute_url(url))?;
+        {
+            let mut pairs = url.query_pairs_mut();
+            query.serialize(serde_urlencoded::Serializer::new(&mut pairs))?;
+        }
+        if url.query() == Some("") {
+            url.set_query(None);
+        }
+
+        let request = Request {
+            method: Method::GET,
+            url,
+            headers: HeaderMap::new(),
         };
-        let request = self.inner.get(url).query(&query).build()?;
-        info!("Request: {}", request.url());
+        info!("Request: {}", request.url);
 
-        let response = self.inner.execute(request).await?;
+        Ok((request, family))
+    }
+
+    /// `attempts` counts the attempts sent so far, shared with coalesced requests.
+    async fn execute(
+        &self,
+        context: &RequestContext,
+        request: Request,
+        family: EndpointFamily,
+        attempts: &mut Arc<AtomicU32>,
+    ) -> Result<Response> {
+        let key = request.url.to_string();
+        if let Some(response) = self.cached(&key, family).await {
+            debug!("Cache hit: {key}");
+            return Ok(response);
+        }
+
+        match &self.inner.single_flight {
+            Some(single_flight) => {
+                let client = self.clone();
+                let context = context.clone();
+                let shared_key = key.clone();
+                single_flight
+                    .run(&key, attempts, |attempts| async move {
+                        client
+                            .fetch(&shared_key, family, request, context, &attempts)
+                            .await
+                    })
+                    .await
+            }
+            None => {
+                self.fetch(&key, family, request, context.clone(), attempts)
+                    .await
+            }
+        }
+    }
 
-        map_api_error(response).await
+    /// Sends `request` and stores the response in the caches.
+    ///
+    /// Run once for coalesced requests.
+    async fn fetch(
+        &self,
+        key: &str,
+        family: EndpointFamily,
+        request: Request,
+        context: RequestContext,
+        attempts: &AtomicU32,
+    ) -> Result<Response> {
+        let response = self.execute_with_retry(request, context, attempts).await?;
+        if let Some(cache) = &self.inner.cache {
+            cache.insert(key, family, &response, Duration::ZERO);
+        }
+        #[cfg(feature = "disk-cache")]
+        if let Some(disk_cache) = &self.inner.disk_cache {
+            disk_cache.insert(key, family, &response).await;
+        }
+        Ok(response)
     }
+
+    /// A hit of the disk cache is copied to the in-memory cache.
+    #[cfg_attr(not(feature = "disk-cache"), allow(unused_variables))]
+    async fn cached(&self, key: &str, family: EndpointFamily) -> Option<Response> {
+        if let Some(response) = self.inner.cache.as_ref().and_then(|cache| cache.get(key)) {
+            return Some(response);
+        }
+        #[cfg(feature = "disk-cache")]
+        if let Some(disk_cache) = &self.inner.disk_cache {
+            let (response, age) = disk_cache.get(key, family).await?;
+            if let Some(cache) = &self.inner.cache {
+                cache.insert(key, family, &response, age);
+            }
+            return Some(response);
+        }
+        None
+    }
+
+    /// Errors are returned with `context` filled with the attempt number and elapsed time.
+    ///
+    /// `attempts` is updated before each attempt is sent.
+    async fn execute_with_retry(
+        &self,
+        request: Request,
+        mut context: RequestContext,
+        attempts: &AtomicU32,
+    ) -> Result<Response> {
+        let start = Instant::now();
+        let mut attempt = 1;
+        loop {
+            attempts.store(attempt, Ordering::Relaxed);
+            let error = match self.send(request.clone()).await {
+                Ok(response) => {
+                    Span::current().record("retries", attempt - 1);
+                    return Ok(response);
+                }
+                Err(error) => error,
+            };
+            let delay = self.inner.retry.as_ref().and_then(|policy| {
+                policy
+                    .next_delay(attempt, start.elapsed(), &error)
+                    .zip(Some(policy))
+            });
+            let Some((delay, policy)) = delay else {
+                Span::current().record("retries", attempt - 1);
+                context.attempt = attempt;
+                context.elapsed = start.elapsed();
+                return Err(error.with_context(context));
+            };
+
+            warn!("Retry after {delay:?} (attempt {attempt}): {error}");
+            if let Some(on_retry) = &policy.on_retry {
+                on_retry(&RetryEvent {
+                    attempt,
+                    delay,
+                    error: &error,
+                });
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn send(&self, request: Request) -> Result<Response> {
+        if let Some(maintenance) = &self.inner.maintenance {
+            maintenance.check()?;
+        }
+        self.send_unchecked(request).await
+    }
+
+    /// Sends `request` regardless of maintenance state.
+    async fn send_unchecked(&self, mut request: Request) -> Result<Response> {
+        if let Some(quota) = &self.inner.quota {
+            quota.check(self.options.priority == Priority::Background)?;
+        }
+        let _permit = match &self.inner.concurrency_limiter {
+            Some(limiter) => Some(limiter.acquire(self.options.priority).await),
+            None => None,
+        };
+        if let Some(rate_limiter) = &self.inner.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        let (key_index, key) = self.inner.keys.select();
+        // empty if no key is configured, e.g. `DfClient::default()`
+        if !key.is_empty() {
+            request.headers.insert("apikey", key);
+        }
+        if let Some(quota) = &self.inner.quota {
+            quota.record(key_index);
+        }
+
+        let endpoint = request.url.path().to_owned();
+        let result = match self
+            .inner
+            .middlewares
+            .send(self.inner.transport.as_ref(), request)
+            .await
+        {
+            Ok(response) => map_api_error(&endpoint, response),
+            Err(e) => Err(e),
+        };
+        self.inner.keys.report(key_index, &result);
+        if let Some(maintenance) = &self.inner.maintenance {
+            maintenance.observe(&result);
+            if maintenance.start_probing() {
+                self.spawn_maintenance_probe();
+            }
+        }
+        result
+    }
+}
+
+/// # Schema Drift
+impl DfClient {
+    /// `None` if not enabled by [`DfClientBuilder::schema_drift`].
+    pub fn schema_drift(&self) -> Option<&SchemaDrift> {
+        self.inner.schema_drift.as_ref()
+    }
+}
+
+/// # Maintenance
+impl DfClient {
+    /// Always [`MaintenanceState::Available`] if not enabled by [`DfClientBuilder::maintenance`].
+    pub fn maintenance(&self) -> MaintenanceState {
+        self.inner
+            .maintenance
+            .as_ref()
+            .map_or(MaintenanceState::Available, Maintenance::state)
+    }
+
+    /// Receiver notified on every change of [`DfClient::maintenance`].
+    ///
+    /// `None` if not enabled by [`DfClientBuilder::maintenance`].
+    pub fn subscribe_maintenance(&self) -> Option<watch::Receiver<MaintenanceState>> {
+        self.inner.maintenance.as_ref().map(Maintenance::subscribe)
+    }
+
+    /// Probes the API in background until the maintenance is over.
+    ///
+    /// Without a tokio runtime, requests are let through after the back-off window instead.
+    fn spawn_maintenance_probe(&self) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            if let Some(maintenance) = &self.inner.maintenance {
+                maintenance.stop_probing();
+            }
+            return;
+        };
+
+        // the probe should not keep the client alive
+        let inner = Arc::downgrade(&self.inner);
+        runtime.spawn(async move {
+            loop {
+                let Some(interval) = inner.upgrade().and_then(|inner| {
+                    let maintenance = inner.maintenance.as_ref()?;
+                    Some(maintenance.config().probe_interval)
+                }) else {
+                    return;
+                };
+                tokio::time::sleep(interval).await;
+
+                let Some(client) = inner.upgrade().map(|inner| DfClient {
+                    inner,
+                    options: Default::default(),
+                }) else {
+                    return;
+                };
+                if !client.probe_maintenance().await {
+                    return;
+                }
+            }
+        });
+    }
+
+    /// Returns `true` if still under maintenance.
+    async fn probe_maintenance(&self) -> bool {
+        let Some(maintenance) = &self.inner.maintenance else {
+            return false;
+        };
+        if let Ok((request, _)) = self.build_request::<()>("/servers", None) {
+            let _ = self.send_unchecked(request).await;
+        }
+
+        let still = maintenance.state().is_maintenance();
+        if !still {
+            maintenance.stop_probing();
+        }
+        still
+    }
+}
+
+/// # API Key
+impl DfClient {
+    /// Usage counters of each API key.
+    pub fn key_usage(&self) -> Vec<KeyUsage> {
+        self.inner.keys.usage()
+    }
+
+    /// Requests sent today (KST) with each API key.
+    ///
+    /// `None` if not enabled by [`DfClientBuilder::quota`].
+    pub fn quota_usage(&self) -> Option<QuotaUsage> {
+        self.inner.quota.as_ref().map(Quota::usage)
+    }
+}
+
+/// # Cache
+impl DfClient {
+    /// `None` if the cache is not enabled by [`DfClientBuilder::cache`].
+    pub fn cache(&self) -> Option<&ResponseCache> {
+        self.inner.cache.as_ref()
+    }
+
+    /// `None` if the cache is not enabled by [`DfClientBuilder::disk_cache`].
+    #[cfg(feature = "disk-cache")]
+    pub fn disk_cache(&self) -> Option<&cache::DiskCache> {
+        self.inner.disk_cache.as_ref()
+    }
+}
+
+impl DfClient {
+    /// `path` is relative to the image base url.
+    pub(crate) fn image_url(&self, path: &str) -> String {
+        format!("{}{}", self.inner.image_base_url, path)
+    }
+}
+
+/// `cain` of `/servers/cain/characters`
+fn server_of(url: &str) -> Option<&str> {
+    let path = url.split('?').next()?;
+    let mut segments = path.split('/').skip_while(|segment| *segment != "servers");
+    segments.nth(1).filter(|server| !server.is_empty())
 }
 
-async fn map_api_error(response: Response) -> Result<Response> {
-    if response.status().is_success() {
+fn map_api_error(endpoint: &str, response: Response) -> Result<Response> {
+    if response.status.is_success() {
         return Ok(response);
     }
 
-    let err = ResponseError::from_response(response).await;
+    let err = ResponseError::from_response(endpoint, &response)?;
     error!("Response error: {}", err);
     Err(err.into())
 }
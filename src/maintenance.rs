@@ -0,0 +1,130 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use tokio::{sync::watch, time::Instant};
+use tracing::{info, warn};
+
+use crate::{error::ErrorKind, transport::Response, Result};
+
+/// Pause requests while the API is under maintenance ([`ErrorCode::DNF980`]).
+///
+/// [`ErrorCode::DNF980`]: crate::error::ErrorCode::DNF980
+#[derive(Debug, Clone, Copy)]
+pub struct MaintenanceConfig {
+    /// Requests fail with [`ErrorKind::Maintenance`] for this long after `DNF980`.
+    /// Extended whenever the API still answers `DNF980`.
+    pub backoff: Duration,
+    /// Interval of probe requests sent in background until the API is back.
+    pub probe_interval: Duration,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            backoff: Duration::from_secs(5 * 60),
+            probe_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintenanceState {
+    Available,
+    /// Requests are short-circuited until `until`.
+    Maintenance {
+        until: Instant,
+    },
+}
+
+impl MaintenanceState {
+    pub fn is_maintenance(&self) -> bool {
+        matches!(self, Self::Maintenance { .. })
+    }
+}
+
+/// Maintenance state shared by all clones of the client.
+#[derive(Debug)]
+pub(crate) struct Maintenance {
+    config: MaintenanceConfig,
+    state: watch::Sender<MaintenanceState>,
+    probing: AtomicBool,
+}
+
+impl Maintenance {
+    pub(crate) fn new(config: MaintenanceConfig) -> Self {
+        Self {
+            config,
+            state: watch::Sender::new(MaintenanceState::Available),
+            probing: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn config(&self) -> &MaintenanceConfig {
+        &self.config
+    }
+
+    pub(crate) fn state(&self) -> MaintenanceState {
+        *self.state.borrow()
+    }
+
+    pub(crate) fn subscribe(&self) -> watch::Receiver<MaintenanceState> {
+        self.state.subscribe()
+    }
+
+    /// # Errors
+    /// [`ErrorKind::Maintenance`] within the back-off window.
+    pub(crate) fn check(&self) -> Result<()> {
+        match self.state() {
+            MaintenanceState::Maintenance { until } if until > Instant::now() => {
+                Err(ErrorKind::Maintenance {
+                    retry_after: until - Instant::now(),
+                }
+                .into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Updates the state from the result of a request.
+    ///
+    /// Any answer of the API other than `DNF980` means the maintenance is over.
+    pub(crate) fn observe(&self, result: &Result<Response>) {
+        match result {
+            Err(e) if e.is_maintenance() => {
+                let until = Instant::now() + self.config.backoff;
+                let previous = self
+                    .state
+                    .send_replace(MaintenanceState::Maintenance { until });
+                if !previous.is_maintenance() {
+                    warn!("API is under maintenance, pausing requests");
+                }
+            }
+            Err(e) if !matches!(e.kind(), ErrorKind::Response(_)) => {}
+            _ => {
+                self.state.send_if_modified(|state| {
+                    let modified = state.is_maintenance();
+                    if modified {
+                        info!("API is back from maintenance");
+                        *state = MaintenanceState::Available;
+                    }
+                    modified
+                });
+            }
+        }
+    }
+
+    /// `true` if the caller should start probing. Call [`Maintenance::stop_probing`] when done.
+    pub(crate) fn start_probing(&self) -> bool {
+        self.state().is_maintenance()
+            && self
+                .probing
+                .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+    }
+
+    pub(crate) fn stop_probing(&self) {
+        self.probing.store(false, Ordering::Release);
+    }
+}
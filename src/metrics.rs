@@ -0,0 +1,49 @@
+//! Metrics of every handler call via the [`metrics`](https://docs.rs/metrics) facade.
+//! Enabled by `metrics` feature; install a recorder, e.g. a Prometheus exporter, to collect them.
+//!
+//! | Name | Type | Labels |
+//! |------|------|--------|
+//! | `df_requests_total` | counter | `endpoint` |
+//! | `df_errors_total` | counter | `endpoint`, `code` |
+//! | `df_request_duration_seconds` | histogram | `endpoint` |
+//!
+//! `endpoint` is the url template, see [`RequestContext::endpoint`](crate::error::RequestContext::endpoint).
+//!
+//! `code` is the [`ErrorCode`](crate::error::ErrorCode) of Neople API, e.g. `API002`, or else the kind of error:
+//! `reqwest`, `decode`, `unexpected_response`, `invalid_query_parameter`, `maintenance`,
+//! `timeout`, `cancelled`, `quota_exhausted`, `cassette` or `other`.
+
+use std::time::Duration;
+
+use crate::{error::ErrorKind, Error, Result};
+
+pub(crate) fn record<T>(endpoint: &str, result: &Result<T>, latency: Duration) {
+    let endpoint = endpoint.to_owned();
+    metrics::counter!("df_requests_total", "endpoint" => endpoint.clone()).increment(1);
+    metrics::histogram!("df_request_duration_seconds", "endpoint" => endpoint.clone())
+        .record(latency.as_secs_f64());
+    if let Err(e) = result {
+        metrics::counter!("df_errors_total", "endpoint" => endpoint, "code" => error_label(e))
+            .increment(1);
+    }
+}
+
+/// [`ErrorCode`](crate::error::ErrorCode) of Neople API, or the kind of error, e.g. `timeout`.
+fn error_label(error: &Error) -> String {
+    if let Some(code) = error.code() {
+        return code.to_string();
+    }
+    match error.kind() {
+        ErrorKind::Reqwest(_) => "reqwest",
+        ErrorKind::Decode(_) => "decode",
+        ErrorKind::UnexpectedResponse(_) => "unexpected_response",
+        ErrorKind::InvalidQueryParameter(_) => "invalid_query_parameter",
+        ErrorKind::Maintenance { .. } => "maintenance",
+        ErrorKind::Timeout { .. } => "timeout",
+        ErrorKind::Cancelled => "cancelled",
+        ErrorKind::QuotaExhausted { .. } => "quota_exhausted",
+        ErrorKind::Cassette(_) => "cassette",
+        _ => "other",
+    }
+    .to_owned()
+}
@@ -0,0 +1,104 @@
+//! Hooks around every request sent by [`DfClient`](crate::DfClient).
+//!
+//! Middlewares see each attempt (including retries and maintenance probes),
+//! but not responses served from the cache.
+
+use std::{sync::Arc, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::{
+    transport::{Request, Response, Transport},
+    Error, Result,
+};
+
+/// Registered by [`DfClientBuilder::middleware`](crate::DfClientBuilder::middleware).
+///
+/// `before_request` is called in order of registration, the others in reverse order.
+///
+/// ```
+/// use df_rs::{
+///     middleware::Middleware,
+///     transport::{Request, Response},
+/// };
+///
+/// struct UserAgent;
+///
+/// impl Middleware for UserAgent {
+///     fn before_request(&self, request: &mut Request) -> Option<Response> {
+///         request.headers.insert("x-app", "crawler".parse().unwrap());
+///         None
+///     }
+/// }
+/// ```
+pub trait Middleware: Send + Sync + 'static {
+    /// Returns `Some` to skip sending, e.g. to serve a synthetic response.
+    ///
+    /// Following middlewares and the transport are skipped,
+    /// and the response goes through `after_response` of this and previous middlewares.
+    fn before_request(&self, request: &mut Request) -> Option<Response> {
+        let _ = request;
+        None
+    }
+
+    /// Called with non-2xx responses too. `elapsed` is the time since `before_request`.
+    fn after_response(&self, request: &Request, response: &mut Response, elapsed: Duration) {
+        let _ = (request, response, elapsed);
+    }
+
+    /// Called when the transport fails, e.g. connection error.
+    fn on_error(&self, request: &Request, error: &Error, elapsed: Duration) {
+        let _ = (request, error, elapsed);
+    }
+}
+
+/// Chain of [`Middleware`] wrapping a [`Transport`].
+#[derive(Clone, Default)]
+pub(crate) struct Middlewares(Vec<Arc<dyn Middleware>>);
+
+impl Middlewares {
+    pub(crate) fn push(&mut self, middleware: Arc<dyn Middleware>) {
+        self.0.push(middleware);
+    }
+
+    pub(crate) async fn send(
+        &self,
+        transport: &dyn Transport,
+        mut request: Request,
+    ) -> Result<Response> {
+        if self.0.is_empty() {
+            return transport.send(request).await;
+        }
+
+        let start = Instant::now();
+        let mut called = 0;
+        let mut synthetic = None;
+        for middleware in &self.0 {
+            called += 1;
+            synthetic = middleware.before_request(&mut request);
+            if synthetic.is_some() {
+                break;
+            }
+        }
+
+        let result = match synthetic {
+            Some(response) => Ok(response),
+            None => transport.send(request.clone()).await,
+        };
+        let called = self.0[..called].iter().rev();
+        match result {
+            Ok(mut response) => {
+                for middleware in called {
+                    middleware.after_response(&request, &mut response, start.elapsed());
+                }
+                Ok(response)
+            }
+            Err(error) => {
+                for middleware in called {
+                    middleware.on_error(&request, &error, start.elapsed());
+                }
+                Err(error)
+            }
+        }
+    }
+}
@@ -0,0 +1,429 @@
+//! In-process mock of Neople API on localhost. Enabled by `mock` feature.
+//!
+//! Serves fixtures over a real HTTP socket, and fails requests on demand.
+//!
+//! ```no_run
+//! # async fn run() -> std::io::Result<()> {
+//! use df_rs::mock::{Endpoint, Fault, Injection, MockServer};
+//!
+//! let server = MockServer::start().await?;
+//! server.fixture(Endpoint::ItemInfo, r#"{ "itemId": "...", ... }"#);
+//! server.inject(Injection {
+//!     times: Some(1),
+//!     ..Fault::QuotaExceeded.into()
+//! });
+//!
+//! let client = server.client();
+//! // `API002` once, then the fixture
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use hyper::{
+    header::{HeaderValue, CONTENT_TYPE},
+    service::{make_service_fn, service_fn},
+    Body, Method, StatusCode,
+};
+use tokio::sync::oneshot;
+
+use crate::{error::ErrorCode, DfClient, DfClientBuilder};
+
+/// API key expected by [`MockServer::client`]. Any key is accepted.
+pub const API_KEY: &str = "mock-api-key";
+
+/// Path prefix of API endpoints.
+const API_PREFIX: &str = "/df";
+/// Path prefix of image endpoints.
+const IMAGE_PREFIX: &str = "/image/df";
+
+/// Served by image endpoints without fixture.
+const PLACEHOLDER_IMAGE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Endpoints served by [`MockServer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// `/servers`
+    Servers,
+    /// `/servers/{server}/characters`
+    CharacterSearch,
+    /// `/servers/{server}/characters/{id}`
+    CharacterInfo,
+    /// `/servers/{server}/characters/{id}/timeline`
+    CharacterTimeline,
+    /// `/servers/{server}/characters/{id}/equip/equipment`
+    CharacterEquipments,
+    /// `/servers/{server}/characters/{id}/equip/avatar`
+    CharacterAvatars,
+    /// `/servers/{server}/characters/{id}/equip/creature`
+    CharacterCreature,
+    /// `/servers/{server}/characters/{id}/equip/flag`
+    CharacterFlag,
+    /// `/servers/{server}/characters/{id}/equip/talisman`
+    CharacterTalismans,
+    /// `/servers/{server}/characters/{id}/skill/buff/equip/equipment`
+    BuffEquipments,
+    /// `/servers/{server}/characters/{id}/skill/buff/equip/avatar`
+    BuffAvatars,
+    /// `/servers/{server}/characters/{id}/skill/buff/equip/creature`
+    BuffCreature,
+    /// `/items`
+    ItemSearch,
+    /// `/items/{id}`
+    ItemInfo,
+    /// `/multi/items`
+    MultiItemInfo,
+    /// `/auction`
+    AuctionSearch,
+    /// `/auction-sold`
+    AuctionSold,
+    /// Image of `/servers/{server}/characters/{id}`
+    CharacterImage,
+    /// Image of `/items/{id}`
+    ItemImage,
+}
+
+impl Endpoint {
+    /// `None` if not served.
+    fn parse(path: &str) -> Option<Self> {
+        let (image, rest) = match path.strip_prefix(IMAGE_PREFIX) {
+            Some(rest) => (true, rest),
+            None => (false, path.strip_prefix(API_PREFIX)?),
+        };
+        let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+
+        use Endpoint::*;
+        let endpoint = match (image, segments.as_slice()) {
+            (true, ["servers", _, "characters", _]) => CharacterImage,
+            (true, ["items", _]) => ItemImage,
+            (true, _) => return None,
+            (_, ["servers"]) => Servers,
+            (_, ["servers", _, "characters"]) => CharacterSearch,
+            (_, ["servers", _, "characters", _]) => CharacterInfo,
+            (_, ["servers", _, "characters", _, "timeline"]) => CharacterTimeline,
+            (_, ["servers", _, "characters", _, "equip", dst]) => match *dst {
+                "equipment" => CharacterEquipments,
+                "avatar" => CharacterAvatars,
+                "creature" => CharacterCreature,
+                "flag" => CharacterFlag,
+                "talisman" => CharacterTalismans,
+                _ => return None,
+            },
+            (_, ["servers", _, "characters", _, "skill", "buff", "equip", dst]) => match *dst {
+                "equipment" => BuffEquipments,
+                "avatar" => BuffAvatars,
+                "creature" => BuffCreature,
+                _ => return None,
+            },
+            (_, ["items"]) => ItemSearch,
+            (_, ["items", _]) => ItemInfo,
+            (_, ["multi", "items"]) => MultiItemInfo,
+            (_, ["auction"]) => AuctionSearch,
+            (_, ["auction-sold"]) => AuctionSold,
+            _ => return None,
+        };
+        Some(endpoint)
+    }
+
+    fn is_image(self) -> bool {
+        matches!(self, Self::CharacterImage | Self::ItemImage)
+    }
+
+    /// Response without fixture: empty `rows` for searches, not found otherwise.
+    fn default_response(self) -> Fixture {
+        use Endpoint::*;
+        match self {
+            Servers | CharacterSearch | ItemSearch | MultiItemInfo | AuctionSearch
+            | AuctionSold => Fixture::json(r#"{"rows":[]}"#),
+            CharacterImage | ItemImage => Fixture::image(PLACEHOLDER_IMAGE),
+            ItemInfo => Fixture::error(404, ErrorCode::DNF003),
+            _ => Fixture::error(404, ErrorCode::DNF001),
+        }
+    }
+}
+
+/// Failure injected by [`MockServer::inject`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// `429` with [`ErrorCode::API002`].
+    QuotaExceeded,
+    /// `503` with [`ErrorCode::DNF980`].
+    Maintenance,
+    /// Plain text body with `status`, like an error page of a gateway.
+    ServerError(u16),
+    /// Neople API error with any code.
+    Error { status: u16, code: ErrorCode },
+}
+
+impl Fault {
+    fn response(&self) -> Fixture {
+        match self {
+            Self::QuotaExceeded => Fixture::error(429, ErrorCode::API002),
+            Self::Maintenance => Fixture::error(503, ErrorCode::DNF980),
+            Self::ServerError(status) => Fixture {
+                status: *status,
+                content_type: "text/plain",
+                body: Bytes::from_static(b"Server Error"),
+            },
+            Self::Error { status, code } => Fixture::error(*status, code.clone()),
+        }
+    }
+}
+
+/// Where and how many times a [`Fault`] is injected.
+#[derive(Debug, Clone)]
+pub struct Injection {
+    pub fault: Fault,
+    /// Only requests of this endpoint fail. Default: every endpoint
+    pub endpoint: Option<Endpoint>,
+    /// Number of requests failing. Default: until [`MockServer::clear_injections`]
+    pub times: Option<usize>,
+}
+
+impl From<Fault> for Injection {
+    fn from(fault: Fault) -> Self {
+        Self {
+            fault,
+            endpoint: None,
+            times: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Fixture {
+    status: u16,
+    content_type: &'static str,
+    body: Bytes,
+}
+
+impl Fixture {
+    fn json(body: impl Into<Bytes>) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json;charset=UTF-8",
+            body: body.into(),
+        }
+    }
+
+    fn image(body: impl Into<Bytes>) -> Self {
+        Self {
+            status: 200,
+            content_type: "image/png",
+            body: body.into(),
+        }
+    }
+
+    fn error(status: u16, code: ErrorCode) -> Self {
+        Self {
+            status,
+            ..Self::json(format!(
+                r#"{{"error":{{"status":{status},"code":"{code}","message":"mock error"}}}}"#
+            ))
+        }
+    }
+
+    fn into_response(self) -> hyper::Response<Body> {
+        let mut response = hyper::Response::new(Body::from(self.body));
+        *response.status_mut() =
+            StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(self.content_type));
+        response
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    fixtures: HashMap<Endpoint, Fixture>,
+    /// Take precedence over `fixtures`.
+    path_fixtures: HashMap<String, Fixture>,
+    injections: Vec<Injection>,
+    /// Path and query of received requests.
+    requests: Vec<String>,
+}
+
+impl State {
+    fn handle(&mut self, request: &hyper::Request<Body>) -> Fixture {
+        let uri = request.uri();
+        self.requests.push(
+            uri.path_and_query()
+                .map_or_else(|| uri.path().to_owned(), ToString::to_string),
+        );
+
+        if request.method() != Method::GET {
+            return Fixture::error(405, ErrorCode::API900);
+        }
+        if !request.headers().contains_key("apikey") {
+            return Fixture::error(400, ErrorCode::API000);
+        }
+        let Some(endpoint) = Endpoint::parse(uri.path()) else {
+            return Fixture::error(404, ErrorCode::API900);
+        };
+
+        let injection = self
+            .injections
+            .iter_mut()
+            .find(|i| i.endpoint.is_none_or(|e| e == endpoint) && i.times != Some(0));
+        if let Some(injection) = injection {
+            if let Some(times) = &mut injection.times {
+                *times -= 1;
+            }
+            return injection.fault.response();
+        }
+
+        self.path_fixtures
+            .get(uri.path())
+            .or_else(|| self.fixtures.get(&endpoint))
+            .cloned()
+            .unwrap_or_else(|| endpoint.default_response())
+    }
+}
+
+/// Mock of Neople API listening on `127.0.0.1`. Stopped on drop.
+///
+/// Without fixtures, searches return empty `rows` and others return not found.
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+/// # Constructor
+impl MockServer {
+    /// Listens on a free port of `127.0.0.1`, in a task of the current tokio runtime.
+    ///
+    /// # Errors
+    /// If the port cannot be bound.
+    ///
+    /// # Panics
+    /// Panics if called outside of a tokio runtime.
+    pub async fn start() -> io::Result<Self> {
+        let state = Arc::new(Mutex::new(State::default()));
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let fixture = state.lock().unwrap().handle(&request);
+                    async move { Ok::<_, Infallible>(fixture.into_response()) }
+                }))
+            }
+        });
+
+        let server = hyper::Server::try_bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+            .map_err(|e| io::Error::new(io::ErrorKind::AddrNotAvailable, e))?
+            .serve(make_service);
+        let addr = server.local_addr();
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            let _ = stopped.await;
+        }));
+
+        Ok(Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        })
+    }
+}
+
+/// # Client
+impl MockServer {
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// For [`DfClientBuilder::base_url`].
+    pub fn base_url(&self) -> String {
+        format!("http://{}{API_PREFIX}", self.addr)
+    }
+
+    /// For [`DfClientBuilder::image_base_url`].
+    pub fn image_base_url(&self) -> String {
+        format!("http://{}{IMAGE_PREFIX}", self.addr)
+    }
+
+    /// Builder pointed at this server, with [`API_KEY`].
+    pub fn builder(&self) -> DfClientBuilder {
+        let mut builder = DfClient::builder();
+        builder
+            .api_key(API_KEY)
+            .base_url(self.base_url())
+            .image_base_url(self.image_base_url());
+        builder
+    }
+
+    /// # Panics
+    /// Panics if the underlying [`reqwest::Client`] cannot be built.
+    pub fn client(&self) -> DfClient {
+        self.builder().build().expect("failed to build DfClient")
+    }
+}
+
+/// # Fixture
+impl MockServer {
+    /// Serve `body` for every request of `endpoint`.
+    pub fn fixture(&self, endpoint: Endpoint, body: impl Into<Bytes>) -> &Self {
+        let fixture = match endpoint.is_image() {
+            true => Fixture::image(body),
+            false => Fixture::json(body),
+        };
+        self.state().fixtures.insert(endpoint, fixture);
+        self
+    }
+
+    /// Serve `body` for requests of `path`, e.g. `/df/items/{id}`. Preferred over [`MockServer::fixture`].
+    pub fn fixture_path(&self, path: &str, body: impl Into<Bytes>) -> &Self {
+        let fixture = match path.starts_with(IMAGE_PREFIX) {
+            true => Fixture::image(body),
+            false => Fixture::json(body),
+        };
+        self.state().path_fixtures.insert(path.to_owned(), fixture);
+        self
+    }
+
+    /// Fail requests before serving fixtures.
+    ///
+    /// Injections are checked in order of registration.
+    pub fn inject(&self, injection: impl Into<Injection>) -> &Self {
+        self.state().injections.push(injection.into());
+        self
+    }
+
+    pub fn clear_injections(&self) -> &Self {
+        self.state().injections.clear();
+        self
+    }
+
+    /// Path and query of received requests, e.g. `/df/items/{id}`.
+    pub fn requests(&self) -> Vec<String> {
+        self.state().requests.clone()
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.state().requests.len()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
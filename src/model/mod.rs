@@ -152,28 +152,32 @@ impl<'de> Deserialize<'de> for Status {
             name: String,
             value: serde_json::Value,
         }
+        use serde::de::Error;
+
         let map = Vec::<StatusInner>::deserialize(deserializer)?
             .into_iter()
             .map(|inner| {
                 let k = inner.name;
                 let v = match inner.value {
                     serde_json::Value::String(mut v) => match v.pop() {
-                        Some(suffix) if suffix == '%' => StatusValue {
-                            value: v.parse().unwrap(),
+                        Some('%') => StatusValue {
+                            value: v.parse().map_err(D::Error::custom)?,
                             suffix: Some('%'),
                         },
-                        Some(_) => panic!("value should be ends with '%'"),
-                        None => panic!("value should not be empty"),
+                        Some(_) => return Err(D::Error::custom("value should be ends with '%'")),
+                        None => return Err(D::Error::custom("value should not be empty")),
                     },
                     serde_json::Value::Number(v) => StatusValue {
-                        value: v.as_f64().unwrap(),
+                        value: v
+                            .as_f64()
+                            .ok_or_else(|| D::Error::custom("value should be f64"))?,
                         suffix: None,
                     },
-                    _ => panic!("value should be string or number"),
+                    _ => return Err(D::Error::custom("value should be string or number")),
                 };
-                (k, v)
+                Ok((k, v))
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
         Ok(Self(map))
     }
 }
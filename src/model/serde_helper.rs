@@ -24,14 +24,14 @@ where
         where
             E: serde::de::Error,
         {
-            Ok(v.parse().unwrap())
+            v.parse().map_err(E::custom)
         }
 
         fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
         where
             E: serde::de::Error,
         {
-            Ok(v.try_into().unwrap())
+            v.try_into().map_err(E::custom)
         }
     }
 
@@ -77,7 +77,9 @@ where
     match creatures {
         Some(mut arr) => match arr.pop() {
             Some(creature) => Ok(Some(creature)),
-            None => panic!("buff creature should be at least one"),
+            None => Err(serde::de::Error::custom(
+                "buff creature should be at least one",
+            )),
         },
         None => Ok(None),
     }
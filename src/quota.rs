@@ -0,0 +1,181 @@
+use std::{sync::Mutex, time::Duration};
+
+use time::{macros::offset, Date, OffsetDateTime, UtcOffset};
+use tracing::warn;
+
+use crate::{error::ErrorKind, Result};
+
+/// Offset of KST (UTC+9), in which the daily quota of Neople API is reset.
+const KST: UtcOffset = offset!(+9);
+
+/// Budget of requests per API key per day (KST).
+///
+/// ```
+/// use df_rs::quota::QuotaConfig;
+///
+/// let config = QuotaConfig {
+///     refuse_background: true,
+///     ..QuotaConfig::new(100_000)
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct QuotaConfig {
+    /// Requests per API key per day.
+    pub daily_budget: u64,
+    /// Warn once a key has used this fraction of `daily_budget`. Default: `0.8`
+    pub warn_ratio: f64,
+    /// Refuse [`Priority::Background`] requests with [`ErrorKind::QuotaExhausted`]
+    /// once every key has used `daily_budget`. Default: `false`
+    ///
+    /// [`Priority::Background`]: crate::concurrency::Priority::Background
+    pub refuse_background: bool,
+}
+
+impl QuotaConfig {
+    pub fn new(daily_budget: u64) -> Self {
+        Self {
+            daily_budget,
+            warn_ratio: 0.8,
+            refuse_background: false,
+        }
+    }
+}
+
+/// Requests sent today, returned by [`DfClient::quota_usage`](crate::DfClient::quota_usage).
+#[derive(Debug, Clone)]
+pub struct QuotaUsage {
+    /// Today in KST.
+    pub day: Date,
+    /// [`QuotaConfig::daily_budget`]
+    pub budget: u64,
+    /// Requests of each key, in order of [`DfClientBuilder::api_keys`](crate::DfClientBuilder::api_keys).
+    pub requests: Vec<u64>,
+    /// Until the next day in KST.
+    pub resets_in: Duration,
+}
+
+impl QuotaUsage {
+    /// Requests of all keys.
+    pub fn total(&self) -> u64 {
+        self.requests.iter().sum()
+    }
+
+    /// Remaining budget of all keys.
+    pub fn remaining(&self) -> u64 {
+        self.requests
+            .iter()
+            .map(|requests| self.budget.saturating_sub(*requests))
+            .sum()
+    }
+
+    /// Every key has used the budget.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining() == 0
+    }
+}
+
+/// Daily counters shared by all clones of the client.
+#[derive(Debug)]
+pub(crate) struct Quota {
+    config: QuotaConfig,
+    /// Masked keys, for warnings.
+    keys: Vec<String>,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    day: Date,
+    requests: Vec<u64>,
+    warned: Vec<bool>,
+}
+
+impl Quota {
+    pub(crate) fn new(config: QuotaConfig, keys: Vec<String>) -> Self {
+        let (day, _) = kst_today();
+        Self {
+            config,
+            state: Mutex::new(State {
+                day,
+                requests: vec![0; keys.len()],
+                warned: vec![false; keys.len()],
+            }),
+            keys,
+        }
+    }
+
+    /// # Errors
+    /// [`ErrorKind::QuotaExhausted`] if refused by [`QuotaConfig::refuse_background`].
+    pub(crate) fn check(&self, background: bool) -> Result<()> {
+        if !(background && self.config.refuse_background) {
+            return Ok(());
+        }
+        let usage = self.usage();
+        if usage.is_exhausted() {
+            return Err(ErrorKind::QuotaExhausted {
+                used: usage.total(),
+                budget: usage.budget * usage.requests.len() as u64,
+                resets_in: usage.resets_in,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Counts a request sent with the key at `index`.
+    pub(crate) fn record(&self, index: usize) {
+        let budget = self.config.daily_budget;
+        let mut state = self.state();
+        state.requests[index] += 1;
+        let requests = state.requests[index];
+
+        if !state.warned[index] && requests as f64 >= budget as f64 * self.config.warn_ratio {
+            state.warned[index] = true;
+            warn!(
+                "API key {} used {requests} of {budget} requests today",
+                self.keys[index]
+            );
+        }
+        if requests == budget {
+            warn!(
+                "API key {} used up {budget} requests today",
+                self.keys[index]
+            );
+        }
+    }
+
+    pub(crate) fn usage(&self) -> QuotaUsage {
+        let (_, resets_in) = kst_today();
+        let state = self.state();
+        QuotaUsage {
+            day: state.day,
+            budget: self.config.daily_budget,
+            requests: state.requests.clone(),
+            resets_in,
+        }
+    }
+
+    /// State of today, reset if the day has changed.
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        let (today, _) = kst_today();
+        let mut state = self.state.lock().unwrap();
+        if state.day != today {
+            state.day = today;
+            state.requests.iter_mut().for_each(|requests| *requests = 0);
+            state.warned.iter_mut().for_each(|warned| *warned = false);
+        }
+        state
+    }
+}
+
+/// Today in KST, and the time until the next day.
+fn kst_today() -> (Date, Duration) {
+    let now = OffsetDateTime::now_utc().to_offset(KST);
+    let today = now.date();
+    let tomorrow = today
+        .next_day()
+        .expect("not the end of time")
+        .midnight()
+        .assume_offset(KST);
+    (today, (tomorrow - now).try_into().unwrap_or_default())
+}
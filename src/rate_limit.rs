@@ -0,0 +1,94 @@
+use std::{num::NonZeroU32, sync::Mutex, time::Duration};
+
+use tokio::time::Instant;
+
+/// Client-side request rate limit of [`DfClient`](crate::DfClient).
+///
+/// Shared by all clones of the client. Requests wait until a token is available.
+///
+/// ```
+/// # use std::num::NonZeroU32;
+/// # use df_rs::rate_limit::RateLimit;
+/// let limit = RateLimit {
+///     per_second: NonZeroU32::new(10),
+///     per_minute: NonZeroU32::new(300),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimit {
+    pub per_second: Option<NonZeroU32>,
+    pub per_minute: Option<NonZeroU32>,
+}
+
+/// Token buckets built from [`RateLimit`].
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    buckets: Mutex<Vec<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    /// Tokens per second.
+    refill_rate: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        let now = Instant::now();
+        let bucket = |capacity: NonZeroU32, period: Duration| Bucket {
+            capacity: capacity.get() as f64,
+            tokens: capacity.get() as f64,
+            refill_rate: capacity.get() as f64 / period.as_secs_f64(),
+            last_refill: now,
+        };
+
+        let buckets = [
+            limit.per_second.map(|n| bucket(n, Duration::from_secs(1))),
+            limit.per_minute.map(|n| bucket(n, Duration::from_secs(60))),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        Self {
+            buckets: Mutex::new(buckets),
+        }
+    }
+
+    /// Waits until every bucket has a token, then takes one from each.
+    pub(crate) async fn acquire(&self) {
+        while let Some(wait) = self.try_acquire() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Returns time to wait if any bucket is empty.
+    fn try_acquire(&self) -> Option<Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let now = Instant::now();
+
+        let wait = buckets
+            .iter_mut()
+            .filter_map(|bucket| {
+                bucket.refill(now);
+                (bucket.tokens < 1.0)
+                    .then(|| Duration::from_secs_f64((1.0 - bucket.tokens) / bucket.refill_rate))
+            })
+            .max();
+        if wait.is_none() {
+            buckets.iter_mut().for_each(|bucket| bucket.tokens -= 1.0);
+        }
+        wait
+    }
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_rate).min(self.capacity);
+        self.last_refill = now;
+    }
+}
@@ -0,0 +1,86 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::Error;
+
+/// Called before each retry.
+pub type RetryHook = Arc<dyn Fn(&RetryEvent) + Send + Sync>;
+
+/// Retry policy of [`DfClient`](crate::DfClient).
+///
+/// Retries errors for which [`Error::is_retryable`] returns `true`, with exponential backoff.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use df_rs::retry::RetryPolicy;
+/// let policy = RetryPolicy {
+///     max_attempts: 5,
+///     deadline: Some(Duration::from_secs(30)),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    /// Randomize each delay between a half and the whole of it.
+    pub jitter: bool,
+    /// Total time limit since the first attempt. No retry is made past it.
+    pub deadline: Option<Duration>,
+    pub on_retry: Option<RetryHook>,
+}
+
+#[derive(Debug)]
+pub struct RetryEvent<'a> {
+    /// Number of the failed attempt, starting from 1.
+    pub attempt: u32,
+    /// Delay before the next attempt.
+    pub delay: Duration,
+    pub error: &'a Error,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: true,
+            deadline: None,
+            on_retry: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the next attempt, or `None` if `error` should be returned.
+    ///
+    /// `attempt` is the number of the failed attempt, starting from 1.
+    pub(crate) fn next_delay(
+        &self,
+        attempt: u32,
+        elapsed: Duration,
+        error: &Error,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts || !error.is_retryable() {
+            return None;
+        }
+
+        let exp = self.multiplier.powi(attempt as i32 - 1);
+        // clamp before converting, as the exponential may overflow `Duration`
+        let secs = (self.initial_backoff.as_secs_f64() * exp).min(self.max_backoff.as_secs_f64());
+        let mut delay = Duration::from_secs_f64(secs);
+        if self.jitter {
+            delay = delay.mul_f64(0.5 + fastrand::f64() * 0.5);
+        }
+
+        match self.deadline {
+            Some(deadline) if elapsed + delay > deadline => None,
+            _ => Some(delay),
+        }
+    }
+}
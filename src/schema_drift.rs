@@ -0,0 +1,212 @@
+use std::{collections::BTreeMap, fmt, sync::Mutex};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+use crate::error::DecodeError;
+
+/// Kind of difference between a response and the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DriftKind {
+    /// Field in the response which is not consumed by the model.
+    Unknown,
+    /// Field of the model which is missing in the response, so defaulted.
+    Missing,
+    /// Field which is `null` in the response, but defaulted to a non-null value.
+    Null,
+}
+
+impl DriftKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DriftKind::Unknown => "unknown",
+            DriftKind::Missing => "missing",
+            DriftKind::Null => "null",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaDriftEntry {
+    /// Url template, e.g. `/servers/{server}/characters`.
+    pub endpoint: String,
+    /// JSON path where array indices are omitted, e.g. `rows[].jobName`.
+    pub path: String,
+    pub kind: DriftKind,
+    /// Number of responses in which the drift was found.
+    pub count: u64,
+}
+
+/// Aggregated drifts sorted by endpoint, path and kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SchemaDriftReport {
+    pub entries: Vec<SchemaDriftEntry>,
+}
+
+impl SchemaDriftReport {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// One line per entry: `{endpoint} {kind} {path} (x{count})`
+impl fmt::Display for SchemaDriftReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{} {} {} (x{})",
+                entry.endpoint,
+                entry.kind.name(),
+                entry.path,
+                entry.count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Records differences between responses and models.
+///
+/// Enabled by [`DfClientBuilder::schema_drift`](crate::DfClientBuilder::schema_drift).
+/// Shared by all clones of the client.
+///
+/// Fields are compared with the model serialized again, so fields renamed or reshaped
+/// only on deserialization (e.g. [`BuffEnhance`](crate::model::buff::BuffEnhance)) may be reported.
+#[derive(Debug, Default)]
+pub struct SchemaDrift {
+    drifts: Mutex<BTreeMap<(String, String, DriftKind), u64>>,
+}
+
+impl SchemaDrift {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn report(&self) -> SchemaDriftReport {
+        let drifts = self.drifts.lock().unwrap();
+        SchemaDriftReport {
+            entries: drifts
+                .iter()
+                .map(|((endpoint, path, kind), count)| SchemaDriftEntry {
+                    endpoint: endpoint.clone(),
+                    path: path.clone(),
+                    kind: *kind,
+                    count: *count,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.drifts.lock().unwrap().clear();
+    }
+
+    /// Same as [`DecodeError::decode`], recording drifts of `endpoint`.
+    ///
+    /// `path` is the path of the request for [`DecodeError`].
+    pub(crate) fn decode<T>(
+        &self,
+        endpoint: &str,
+        path: &str,
+        body: &[u8],
+    ) -> Result<T, DecodeError>
+    where
+        T: DeserializeOwned + Serialize,
+    {
+        let mut found = Vec::new();
+        let mut on_ignored = |ignored: serde_ignored::Path| {
+            found.push((json_path(&ignored), DriftKind::Unknown));
+        };
+        let deserializer = &mut serde_json::Deserializer::from_slice(body);
+        let deserializer = serde_ignored::Deserializer::new(deserializer, &mut on_ignored);
+        let model: T = serde_path_to_error::deserialize(deserializer)
+            .map_err(|e| DecodeError::from_path_error(path, body, e))?;
+
+        // body is valid JSON if the model is decoded
+        if let (Ok(json), Ok(serialized)) = (
+            serde_json::from_slice::<Value>(body),
+            serde_json::to_value(&model),
+        ) {
+            diff(&json, &serialized, &mut String::new(), &mut found);
+        }
+
+        if !found.is_empty() {
+            debug!("Schema drift of {endpoint}: {found:?}");
+            let mut drifts = self.drifts.lock().unwrap();
+            found.sort();
+            found.dedup();
+            for (path, kind) in found {
+                *drifts.entry((endpoint.to_owned(), path, kind)).or_default() += 1;
+            }
+        }
+        Ok(model)
+    }
+}
+
+fn json_path(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, .. } => format!("{}[]", json_path(parent)),
+        Path::Map { parent, key } => join(&json_path(parent), key),
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => json_path(parent),
+    }
+}
+
+fn join(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{parent}.{key}")
+    }
+}
+
+/// Compares the response with the re-serialized model.
+///
+/// Unknown fields of `#[serde(flatten)]` models are not reported by `serde_ignored`.
+fn diff(json: &Value, model: &Value, path: &mut String, found: &mut Vec<(String, DriftKind)>) {
+    match (json, model) {
+        (Value::Object(json), Value::Object(model)) => {
+            for key in json.keys().filter(|key| !model.contains_key(*key)) {
+                found.push((join(path, key), DriftKind::Unknown));
+            }
+            for (key, model) in model {
+                let len = path.len();
+                *path = join(path, key);
+                match json.get(key) {
+                    Some(json) => diff(json, model, path, found),
+                    None if is_default(model) => found.push((path.clone(), DriftKind::Missing)),
+                    None => {}
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(json), Value::Array(model)) => {
+            let len = path.len();
+            path.push_str("[]");
+            for (json, model) in json.iter().zip(model) {
+                diff(json, model, path, found);
+            }
+            path.truncate(len);
+        }
+        (Value::Null, model) if !model.is_null() => found.push((path.clone(), DriftKind::Null)),
+        _ => {}
+    }
+}
+
+fn is_default(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Bool(b) => !b,
+        Value::Number(n) => n.as_f64() == Some(0.0),
+        Value::String(s) => s.is_empty(),
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{atomic::AtomicU32, Arc, Mutex},
+};
+
+use futures::{
+    future::{BoxFuture, Shared},
+    FutureExt,
+};
+
+use crate::{transport::Response, Result};
+
+type SharedResponse = Shared<BoxFuture<'static, Result<Response>>>;
+
+/// Coalesces concurrent requests with the same key into one.
+#[derive(Default)]
+pub(crate) struct SingleFlight {
+    in_flight: Mutex<HashMap<String, Flight>>,
+}
+
+struct Flight {
+    future: SharedResponse,
+    /// Attempts sent by the request.
+    attempts: Arc<AtomicU32>,
+    waiters: usize,
+}
+
+impl SingleFlight {
+    /// Runs `f` unless a request with the same `key` is in flight,
+    /// in which case waits for it and returns its result.
+    ///
+    /// `f` is given `attempts` to count the attempts it sends.
+    /// If the request is already in flight, `attempts` is replaced with its counter.
+    ///
+    /// The request is dropped once every waiter has gone away.
+    pub(crate) async fn run<F, Fut>(
+        &self,
+        key: &str,
+        attempts: &mut Arc<AtomicU32>,
+        f: F,
+    ) -> Result<Response>
+    where
+        F: FnOnce(Arc<AtomicU32>) -> Fut,
+        Fut: Future<Output = Result<Response>> + Send + 'static,
+    {
+        let future = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            let flight = in_flight.entry(key.to_owned()).or_insert_with(|| Flight {
+                future: f(attempts.clone()).boxed().shared(),
+                attempts: attempts.clone(),
+                waiters: 0,
+            });
+            flight.waiters += 1;
+            *attempts = flight.attempts.clone();
+            flight.future.clone()
+        };
+
+        let mut guard = Waiter {
+            single_flight: self,
+            key,
+            future: &future,
+            completed: false,
+        };
+        let result = future.clone().await;
+        guard.completed = true;
+        result
+    }
+}
+
+/// Unregisters a waiter of [`SingleFlight::run`] when it completes or is dropped.
+struct Waiter<'a> {
+    single_flight: &'a SingleFlight,
+    key: &'a str,
+    future: &'a SharedResponse,
+    completed: bool,
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self.single_flight.in_flight.lock().unwrap();
+        let Some(flight) = in_flight.get_mut(self.key) else {
+            return;
+        };
+        if !flight.future.ptr_eq(self.future) {
+            return;
+        }
+        flight.waiters -= 1;
+        // completed requests are not reused
+        if self.completed || flight.waiters == 0 {
+            let flight = in_flight.remove(self.key);
+            // dropping an abandoned request may release resources it holds; do it unlocked
+            drop(in_flight);
+            drop(flight);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! HTTP transport used by [`DfClient`](crate::DfClient).
+//!
+//! Every request of every handler goes through [`Transport::send`],
+//! so a custom implementation can serve canned responses without network.
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use reqwest::{header::HeaderMap, Method, StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+use crate::Result;
+
+/// Sends [`Request`] and receives [`Response`].
+///
+/// Non-2xx responses should be returned as `Ok`; they are mapped to [`Error`](crate::Error) by the client.
+pub trait Transport: Send + Sync + 'static {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: Method,
+    /// Absolute url including query string.
+    pub url: Url,
+    /// Contains `apikey`.
+    pub headers: HeaderMap,
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl Response {
+    pub fn new(status: StatusCode, body: impl Into<Bytes>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_slice(&self.body)
+    }
+}
+
+/// Default [`Transport`] using [`reqwest::Client`].
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response>> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .request(request.method, request.url)
+                .headers(request.headers)
+                .send()
+                .await?;
+
+            Ok(Response {
+                status: response.status(),
+                headers: response.headers().clone(),
+                body: response.bytes().await?,
+            })
+        })
+    }
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "blocking")]
+
+mod common;
+
+use std::time::Duration;
+
+use common::{FakeTransport, API_KEY, CHARACTERS, ITEM_INFO};
+use df_rs::{model::Server, ErrorKind};
+use tokio::time::Instant;
+
+#[test]
+fn handlers() {
+    let fake = FakeTransport::new();
+    fake.route("/df/servers/cain/characters", 200, CHARACTERS)
+        .route("/df/items/abc", 200, ITEM_INFO)
+        .route_error("/df/items/unknown", 400, "DNF003");
+    let client = fake.builder().build_blocking().unwrap();
+
+    let characters = client
+        .character()
+        .server(Server::Cain)
+        .name("김철수")
+        .search()
+        .unwrap();
+    assert!(!characters.is_empty());
+
+    let item = client.item().id("abc").info().unwrap();
+    assert_eq!(item.name, "무색 큐브 조각");
+    let err = client.item().id("unknown").info().unwrap_err();
+    assert_eq!(err.code().unwrap().to_string(), "DNF003");
+
+    assert_eq!(fake.request_count(), 3);
+}
+
+#[test]
+fn builder() {
+    let fake = FakeTransport::new();
+    fake.route("/df/items/abc", 200, ITEM_INFO);
+    let client = df_rs::blocking::DfClient::builder()
+        .api_key(API_KEY)
+        .base_url("http://fake.test/df")
+        .transport(fake.clone())
+        .build()
+        .unwrap();
+
+    let item = client.item().id("abc").info().unwrap();
+    assert_eq!(item.name, "무색 큐브 조각");
+}
+
+#[test]
+fn deadline() {
+    let fake = FakeTransport::new();
+    fake.delay(Duration::from_millis(100))
+        .route("/df/items/abc", 200, ITEM_INFO);
+    let client = fake.builder().build_blocking().unwrap();
+
+    let err = client
+        .with_deadline(Instant::now())
+        .item()
+        .id("abc")
+        .info()
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::Timeout { .. }));
+}
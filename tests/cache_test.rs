@@ -0,0 +1,85 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{FakeTransport, AUCTION, ITEM_INFO};
+use df_rs::{
+    cache::{CacheConfig, EndpointFamily},
+    DfClient,
+};
+
+const ITEM_ID: &str = "785e56a0ed4e3efd573da1f56a45217d";
+
+fn client(fake: &FakeTransport, config: CacheConfig) -> DfClient {
+    fake.route(&format!("/df/items/{ITEM_ID}"), 200, ITEM_INFO)
+        .route("/df/items/other", 200, ITEM_INFO)
+        .route("/df/auction", 200, AUCTION);
+    fake.builder().cache(config).build().unwrap()
+}
+
+async fn item_info(client: &DfClient, id: &str) {
+    client.item().id(id).info().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn hit_until_expired() {
+    let fake = FakeTransport::new();
+    let client = client(&fake, Default::default());
+
+    item_info(&client, ITEM_ID).await;
+    item_info(&client, ITEM_ID).await;
+    assert_eq!(fake.request_count(), 1);
+
+    tokio::time::advance(Duration::from_secs(60 * 60 * 24)).await;
+    item_info(&client, ITEM_ID).await;
+    assert_eq!(fake.request_count(), 2);
+}
+
+#[tokio::test]
+async fn auction_is_not_cached_by_default() {
+    let fake = FakeTransport::new();
+    let client = client(&fake, Default::default());
+
+    for _ in 0..2 {
+        client
+            .auction()
+            .name("무색 큐브 조각")
+            .search()
+            .await
+            .unwrap();
+    }
+    assert_eq!(fake.request_count(), 2);
+}
+
+#[tokio::test]
+async fn evict_least_recently_used() {
+    let fake = FakeTransport::new();
+    let client = client(
+        &fake,
+        CacheConfig {
+            capacity: 1,
+            ..Default::default()
+        },
+    );
+
+    item_info(&client, ITEM_ID).await;
+    item_info(&client, "other").await;
+    item_info(&client, ITEM_ID).await;
+    assert_eq!(fake.request_count(), 3);
+    assert_eq!(client.cache().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn invalidate() {
+    let fake = FakeTransport::new();
+    let client = client(&fake, Default::default());
+    let cache = client.cache().unwrap();
+
+    item_info(&client, ITEM_ID).await;
+    cache.invalidate(&format!("http://fake.test/df/items/{ITEM_ID}"));
+    item_info(&client, ITEM_ID).await;
+    assert_eq!(fake.request_count(), 2);
+
+    cache.invalidate_family(EndpointFamily::Item);
+    assert!(cache.is_empty());
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use std::path::PathBuf;
+
+use common::{FakeTransport, API_KEY, ITEM_INFO};
+use df_rs::{
+    cassette::CassetteMode,
+    error::CassetteError,
+    transport::{Request, Response, Transport},
+    DfClient, ErrorKind,
+};
+use futures::future::BoxFuture;
+use reqwest::StatusCode;
+
+fn temp_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("df-rs-{name}-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn record_then_replay() {
+    let path = temp_file("cassette");
+    let fake = FakeTransport::new();
+    fake.route("/df/items/abc", 200, ITEM_INFO)
+        .route_error("/df/items/unknown", 400, "DNF003");
+
+    let recorder = fake
+        .builder()
+        .cassette(CassetteMode::Record(path.clone()))
+        .build()
+        .unwrap();
+    let recorded = recorder.item().id("abc").info_raw().await.unwrap();
+    recorder.item().id("unknown").info().await.unwrap_err();
+
+    let cassette = std::fs::read_to_string(&path).unwrap();
+    assert!(!cassette.contains(API_KEY), "{cassette}");
+
+    // no API key nor network
+    let player = DfClient::builder()
+        .base_url("http://fake.test/df")
+        .cassette(CassetteMode::Replay(path))
+        .build()
+        .unwrap();
+    assert_eq!(player.item().id("abc").info_raw().await.unwrap(), recorded);
+    let err = player.item().id("unknown").info().await.unwrap_err();
+    assert_eq!(err.code().unwrap().to_string(), "DNF003");
+
+    let err = player.item().id("other").info().await.unwrap_err();
+    assert!(
+        matches!(err.kind(), ErrorKind::Cassette(CassetteError::NoMatch { path, .. }) if path == "/df/items/other"),
+        "{err}"
+    );
+    assert_eq!(fake.request_count(), 2);
+}
+
+#[test]
+fn missing_cassette() {
+    let err = DfClient::builder()
+        .cassette(CassetteMode::Replay(temp_file("missing")))
+        .build()
+        .unwrap_err();
+    assert!(
+        matches!(err.kind(), ErrorKind::Cassette(CassetteError::Io { .. })),
+        "{err}"
+    );
+}
+
+/// Serves JSON on the API host and PNG on the image host, which share paths.
+struct HostTransport;
+
+impl Transport for HostTransport {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response, df_rs::Error>> {
+        let body = match request.url.host_str() {
+            Some("api.fake.test") => ITEM_INFO.as_bytes().to_vec(),
+            _ => PNG.to_vec(),
+        };
+        Box::pin(async move { Ok(Response::new(StatusCode::OK, body)) })
+    }
+}
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\x00\xff";
+
+#[tokio::test]
+async fn match_by_host() {
+    let path = temp_file("hosts");
+    let mut builder = DfClient::builder();
+    builder
+        .base_url("http://api.fake.test/df")
+        .image_base_url("http://img.fake.test/df");
+
+    let recorder = builder
+        .clone()
+        .transport(HostTransport)
+        .cassette(CassetteMode::Record(path.clone()))
+        .build()
+        .unwrap();
+    recorder.item().id("abc").image().await.unwrap();
+    recorder.item().id("abc").info().await.unwrap();
+
+    let player = builder
+        .cassette(CassetteMode::Replay(path))
+        .build()
+        .unwrap();
+    assert_eq!(
+        player.item().id("abc").info().await.unwrap().name,
+        "무색 큐브 조각"
+    );
+    assert_eq!(player.item().id("abc").image().await.unwrap(), PNG);
+}
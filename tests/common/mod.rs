@@ -0,0 +1,144 @@
+#![allow(dead_code)]
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use df_rs::{
+    transport::{Request, Response, Transport},
+    DfClient, DfClientBuilder,
+};
+use futures::future::BoxFuture;
+use reqwest::StatusCode;
+
+pub const API_KEY: &str = "test-api-key";
+
+/// In-memory [`Transport`] serving canned responses by url path.
+///
+/// Responses registered for a path are served in order; the last one is repeated.
+#[derive(Clone, Default)]
+pub struct FakeTransport {
+    routes: Arc<Mutex<HashMap<String, VecDeque<Response>>>>,
+    requests: Arc<Mutex<Vec<Request>>>,
+    delay: Arc<Mutex<Duration>>,
+}
+
+impl FakeTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn route(&self, path: &str, status: u16, body: impl Into<String>) -> &Self {
+        self.routes
+            .lock()
+            .unwrap()
+            .entry(path.to_owned())
+            .or_default()
+            .push_back(Response::new(
+                StatusCode::from_u16(status).unwrap(),
+                body.into(),
+            ));
+        self
+    }
+
+    pub fn route_error(&self, path: &str, status: u16, code: &str) -> &Self {
+        self.route(path, status, error_body(status, code))
+    }
+
+    /// Delay every response.
+    pub fn delay(&self, delay: Duration) -> &Self {
+        *self.delay.lock().unwrap() = delay;
+        self
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+
+    pub fn builder(&self) -> DfClientBuilder {
+        let mut builder = DfClient::builder();
+        builder
+            .api_key(API_KEY)
+            .base_url("http://fake.test/df")
+            .image_base_url("http://fake.test/img")
+            .transport(self.clone());
+        builder
+    }
+
+    pub fn client(&self) -> DfClient {
+        self.builder().build().unwrap()
+    }
+}
+
+impl Transport for FakeTransport {
+    fn send(&self, request: Request) -> BoxFuture<'_, Result<Response, df_rs::Error>> {
+        let path = request.url.path().to_owned();
+        self.requests.lock().unwrap().push(request);
+
+        let mut routes = self.routes.lock().unwrap();
+        let response = match routes.get_mut(&path) {
+            Some(queue) if queue.len() > 1 => queue.pop_front().unwrap(),
+            Some(queue) => queue.front().unwrap().clone(),
+            None => Response::new(StatusCode::NOT_FOUND, error_body(404, "API900")),
+        };
+        let delay = *self.delay.lock().unwrap();
+        Box::pin(async move {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Collects formatted tracing output.
+#[derive(Clone, Default)]
+pub struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Buffer {
+    pub fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl io::Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub fn error_body(status: u16, code: &str) -> String {
+    format!(r#"{{"error":{{"status":{status},"code":"{code}","message":"fake error"}}}}"#)
+}
+
+pub const CHARACTERS: &str = r#"{"rows":[{
+    "serverId":"cain","characterId":"c1","characterName":"김철수","level":110,
+    "jobId":"j","jobGrowId":"g","jobName":"귀검사(남)","jobGrowName":"眞 웨펀마스터"
+}]}"#;
+
+pub const ITEM_INFO: &str = r#"{
+    "itemId":"785e56a0ed4e3efd573da1f56a45217d","itemName":"무색 큐브 조각","itemRarity":"커먼",
+    "itemTypeId":"t","itemType":"스태커블","itemTypeDetailId":"d","itemTypeDetail":"재료",
+    "itemAvailableLevel":1,"itemExplain":"","itemExplainDetail":"","itemFlavorText":"",
+    "obtainInfo":{"dungeon":null,"shop":[],"etc":null}
+}"#;
+
+pub const AUCTION: &str = r#"{"rows":[{
+    "auctionNo":1,"regDate":"2023-07-01 00:00:00","expireDate":"2023-07-02 00:00:00",
+    "itemId":"785e56a0ed4e3efd573da1f56a45217d","itemName":"무색 큐브 조각","itemRarity":"커먼",
+    "itemTypeId":"t","itemType":"스태커블","itemTypeDetailId":"d","itemTypeDetail":"재료",
+    "refine":0,"reinforce":0,"amplificationName":null,"itemAvailableLevel":1,
+    "adventureFame":0,"count":100,"currentPrice":1000,"unitPrice":10,"averagePrice":10
+}]}"#;
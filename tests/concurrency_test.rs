@@ -0,0 +1,65 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{FakeTransport, ITEM_INFO};
+use df_rs::concurrency::Priority;
+
+#[tokio::test(start_paused = true)]
+async fn interactive_jumps_the_queue() {
+    let fake = FakeTransport::new();
+    fake.delay(Duration::from_secs(1));
+    for id in ["b1", "b2", "b3", "i1"] {
+        fake.route(&format!("/df/items/{id}"), 200, ITEM_INFO);
+    }
+    let client = fake.builder().max_in_flight(1).build().unwrap();
+    let background = client.with_priority(Priority::Background);
+
+    let mut tasks = Vec::new();
+    for (client, id) in [
+        (&background, "b1"),
+        (&background, "b2"),
+        (&background, "b3"),
+        (&client, "i1"),
+    ] {
+        let client = client.clone();
+        tasks.push(tokio::spawn(async move {
+            client.item().id(id).info().await.unwrap();
+        }));
+        // let the request enqueue
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let order: Vec<_> = fake
+        .requests()
+        .iter()
+        .map(|r| r.url.path().trim_start_matches("/df/items/").to_owned())
+        .collect();
+    assert_eq!(order, ["b1", "i1", "b2", "b3"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn limit_in_flight() {
+    let fake = FakeTransport::new();
+    fake.delay(Duration::from_secs(1))
+        .route("/df/items/abc", 200, ITEM_INFO);
+    let client = fake
+        .builder()
+        .max_in_flight(2)
+        .single_flight(false)
+        .build()
+        .unwrap();
+
+    let start = tokio::time::Instant::now();
+    let results = futures::future::join_all((0..4).map(|_| async {
+        let mut handler = client.item();
+        handler.id("abc").info().await
+    }))
+    .await;
+
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(start.elapsed(), Duration::from_secs(2));
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{FakeTransport, ITEM_INFO};
+use df_rs::{CancellationToken, Error, ErrorKind};
+use tokio::time::Instant;
+
+#[tokio::test(start_paused = true)]
+async fn handler_timeout() {
+    let fake = FakeTransport::new();
+    fake.delay(Duration::from_secs(10))
+        .route("/df/items/abc", 200, ITEM_INFO);
+    let client = fake.client();
+
+    let error = client
+        .item()
+        .id("abc")
+        .timeout(Duration::from_secs(1))
+        .info()
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(error.kind(), ErrorKind::Timeout { elapsed } if *elapsed == Duration::from_secs(1))
+    );
+    let context = error.context().unwrap();
+    assert_eq!(context.endpoint, "/items/{id}");
+    assert_eq!(context.attempt, 1);
+    assert_eq!(context.elapsed, Duration::from_secs(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn deadline_bounds_batch() {
+    let fake = FakeTransport::new();
+    fake.delay(Duration::from_secs(1))
+        .route("/df/items/abc", 200, ITEM_INFO);
+    let batch = fake
+        .client()
+        .with_deadline(Instant::now() + Duration::from_millis(2500));
+
+    let mut results = Vec::new();
+    for _ in 0..3 {
+        let mut handler = batch.item();
+        results.push(handler.id("abc").info().await);
+    }
+
+    assert!(results[0].is_ok());
+    assert!(results[1].is_ok());
+    assert!(matches!(
+        results[2].as_ref().map_err(Error::kind),
+        Err(ErrorKind::Timeout { .. })
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancellation() {
+    let fake = FakeTransport::new();
+    fake.delay(Duration::from_secs(10))
+        .route("/df/items/abc", 200, ITEM_INFO);
+    let token = CancellationToken::new();
+    let client = fake.client().with_cancellation(token.clone());
+
+    let request = tokio::spawn(async move { client.item().id("abc").info().await });
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    token.cancel();
+
+    let error = request.await.unwrap().unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Cancelled));
+}
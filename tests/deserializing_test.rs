@@ -1,48 +1,58 @@
-use std::env;
-
-use df_rs::{
-    error::{ErrorCode, ResponseError},
-    Error,
-};
-
-fn client() -> df_rs::DfClient {
-    df_rs::DfClient::new(&env::var("API_KEY").expect("set API_KEY env var"))
-}
-
-fn is_limit_exceeded<T>(result: &Result<T, Error>) -> bool {
-    match result {
-        Err(Error::Response(ResponseError { code, .. })) => *code == ErrorCode::API002,
-        _ => false,
-    }
-}
-
-macro_rules! retry_if_limit_exceeded {
-    ($e:expr) => {{
-        let mut result = $e;
-        while $crate::is_limit_exceeded(&result) {
-            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-            result = $e;
-        }
-        result
-    }};
+use std::{env, time::Duration};
+
+use df_rs::{cassette::CassetteMode, retry::RetryPolicy};
+
+/// Records responses to `tests/cassettes/{cassette}.json` if `API_KEY` env var is set,
+/// otherwise replays them without network.
+///
+/// Tests are ignored until the cassettes are recorded: `API_KEY=... cargo test -- --ignored`
+fn client(cassette: &str) -> df_rs::DfClient {
+    let path = format!(
+        "{}/tests/cassettes/{cassette}.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let mut builder = df_rs::DfClient::builder();
+    match env::var("API_KEY") {
+        Ok(api_key) => builder
+            .api_key(api_key)
+            .cassette(CassetteMode::Record(path.into())),
+        Err(_) => builder.cassette(CassetteMode::Replay(path.into())),
+    };
+    builder
+        .retry(RetryPolicy {
+            max_attempts: 30,
+            initial_backoff: Duration::from_secs(1),
+            multiplier: 1.0,
+            ..Default::default()
+        })
+        .build()
+        .unwrap()
 }
 
 mod auction {
     use super::client;
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn search() {
-        let result =
-            retry_if_limit_exceeded!(client().auction().name("무색 큐브 조각").search().await);
+        let result = client("auction_search")
+            .auction()
+            .name("무색 큐브 조각")
+            .search()
+            .await;
 
         // println!("{:#?}", result);
         assert!(result.is_ok());
     }
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn sold() {
-        let result =
-            retry_if_limit_exceeded!(client().auction().name("무색 큐브 조각").sold().await);
+        let result = client("auction_sold")
+            .auction()
+            .name("무색 큐브 조각")
+            .sold()
+            .await;
 
         // println!("{:#?}", result);
         assert!(result.is_ok());
@@ -53,23 +63,26 @@ mod item {
     use super::client;
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn search() {
-        let result =
-            retry_if_limit_exceeded!(client().item().name("무색 큐브 조각").search().await);
+        let result = client("item_search")
+            .item()
+            .name("무색 큐브 조각")
+            .search()
+            .await;
 
         // println!("{:#?}", result);
         assert!(result.is_ok());
     }
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn info() {
-        let result = retry_if_limit_exceeded!(
-            client()
-                .item()
-                .id("785e56a0ed4e3efd573da1f56a45217d")
-                .info()
-                .await
-        );
+        let result = client("item_info")
+            .item()
+            .id("785e56a0ed4e3efd573da1f56a45217d")
+            .info()
+            .await;
 
         // println!("{:#?}", result);
         assert!(result.is_ok());
@@ -77,27 +90,37 @@ mod item {
 }
 
 mod character {
-    use df_rs::model::Character;
+    use df_rs::{model::Character, DfClient};
 
     use crate::client;
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn search() {
-        let result = retry_if_limit_exceeded!(client().character().name("김철수").search().await);
+        let result = client("character_search")
+            .character()
+            .name("김철수")
+            .search()
+            .await;
 
         // println!("{:#?}", result);
         assert!(result.is_ok());
     }
 
-    async fn get_characters() -> Result<Vec<Character>, df_rs::Error> {
-        retry_if_limit_exceeded!(client().character().name("김철수").search().await)
+    async fn get_characters(client: &DfClient) -> Result<Vec<Character>, df_rs::Error> {
+        client
+            .character()
+            .name("김철수")
+            .search()
+            .await
             .map(|vec| vec[..vec.len().min(5)].to_vec())
     }
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn info() {
-        let client = client();
-        let characters = get_characters().await.unwrap();
+        let client = client("character_info");
+        let characters = get_characters(&client).await.unwrap();
         // characters.iter().for_each(|character| {
         //     println!(
         //         "https://api.neople.co.kr/df/servers/{}/characters/{}",
@@ -105,7 +128,7 @@ mod character {
         //     );
         // });
         for character in &characters {
-            let result = retry_if_limit_exceeded!(client.character().of(character).info().await);
+            let result = client.character().of(character).info().await;
 
             println!("{:#?}", result);
             assert!(result.is_ok());
@@ -113,9 +136,10 @@ mod character {
     }
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn timeline() {
-        let client = client();
-        let characters = get_characters().await.unwrap();
+        let client = client("character_timeline");
+        let characters = get_characters(&client).await.unwrap();
         // characters.iter().for_each(|character| {
         //     println!(
         //         "https://api.neople.co.kr/df/servers/{}/characters/{}/timeline",
@@ -123,8 +147,7 @@ mod character {
         //     );
         // });
         for character in &characters {
-            let result =
-                retry_if_limit_exceeded!(client.character().of(character).timeline(None).await);
+            let result = client.character().of(character).timeline(None).await;
 
             println!("{:#?}", result);
             assert!(result.is_ok());
@@ -132,9 +155,10 @@ mod character {
     }
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn equipments() {
-        let client = client();
-        let characters = get_characters().await.unwrap();
+        let client = client("character_equipments");
+        let characters = get_characters(&client).await.unwrap();
         // characters.iter().for_each(|character| {
         //     println!(
         //         "https://api.neople.co.kr/df/servers/{}/characters/{}/equip/equipment",
@@ -142,8 +166,7 @@ mod character {
         //     );
         // });
         for character in &characters {
-            let result =
-                retry_if_limit_exceeded!(client.character().of(character).equipments().await);
+            let result = client.character().of(character).equipments().await;
 
             // println!("{:#?}", result);
             assert!(result.is_ok());
@@ -151,9 +174,10 @@ mod character {
     }
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn avatars() {
-        let client = client();
-        let characters = get_characters().await.unwrap();
+        let client = client("character_avatars");
+        let characters = get_characters(&client).await.unwrap();
         // characters.iter().for_each(|character| {
         //     println!(
         //         "https://api.neople.co.kr/df/servers/{}/characters/{}/equip/avatar",
@@ -161,7 +185,7 @@ mod character {
         //     );
         // });
         for character in &characters {
-            let result = retry_if_limit_exceeded!(client.character().of(character).avatars().await);
+            let result = client.character().of(character).avatars().await;
 
             // println!("{:#?}", result);
             assert!(result.is_ok());
@@ -169,9 +193,10 @@ mod character {
     }
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn creature() {
-        let client = client();
-        let characters = get_characters().await.unwrap();
+        let client = client("character_creature");
+        let characters = get_characters(&client).await.unwrap();
         // characters.iter().for_each(|character| {
         //     println!(
         //         "https://api.neople.co.kr/df/servers/{}/characters/{}/equip/creature",
@@ -179,8 +204,7 @@ mod character {
         //     );
         // });
         for character in &characters {
-            let result =
-                retry_if_limit_exceeded!(client.character().of(character).creature().await);
+            let result = client.character().of(character).creature().await;
 
             // println!("{:#?}", result);
             assert!(result.is_ok());
@@ -188,9 +212,10 @@ mod character {
     }
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn flag() {
-        let client = client();
-        let characters = get_characters().await.unwrap();
+        let client = client("character_flag");
+        let characters = get_characters(&client).await.unwrap();
         // characters.iter().for_each(|character| {
         //     println!(
         //         "https://api.neople.co.kr/df/servers/{}/characters/{}/equip/flag",
@@ -198,7 +223,7 @@ mod character {
         //     );
         // });
         for character in &characters {
-            let result = retry_if_limit_exceeded!(client.character().of(character).flag().await);
+            let result = client.character().of(character).flag().await;
 
             // println!("{:#?}", result);
             assert!(result.is_ok());
@@ -206,9 +231,10 @@ mod character {
     }
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn talismans() {
-        let client = client();
-        let characters = get_characters().await.unwrap();
+        let client = client("character_talismans");
+        let characters = get_characters(&client).await.unwrap();
         // characters.iter().for_each(|character| {
         //     println!(
         //         "https://api.neople.co.kr/df/servers/{}/characters/{}/equip/talisman",
@@ -216,8 +242,7 @@ mod character {
         //     );
         // });
         for character in &characters {
-            let result =
-                retry_if_limit_exceeded!(client.character().of(character).talismans().await);
+            let result = client.character().of(character).talismans().await;
 
             // println!("{:#?}", result);
             assert!(result.is_ok());
@@ -225,9 +250,10 @@ mod character {
     }
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn buff_equipments() {
-        let client = client();
-        let characters = get_characters().await.unwrap();
+        let client = client("character_buff_equipments");
+        let characters = get_characters(&client).await.unwrap();
         // characters.iter().for_each(|character| {
         //     println!(
         //         "https://api.neople.co.kr/df/servers/{}/characters/{}/skill/buff/equip/equipment",
@@ -235,9 +261,7 @@ mod character {
         //     );
         // });
         for character in &characters {
-            let result = retry_if_limit_exceeded!(
-                client.character().of(character).buff().equipments().await
-            );
+            let result = client.character().of(character).buff().equipments().await;
 
             println!("{:#?}", result);
             assert!(result.is_ok());
@@ -245,9 +269,10 @@ mod character {
     }
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn buff_avatars() {
-        let client = client();
-        let characters = get_characters().await.unwrap();
+        let client = client("character_buff_avatars");
+        let characters = get_characters(&client).await.unwrap();
         // characters.iter().for_each(|character| {
         //     println!(
         //         "https://api.neople.co.kr/df/servers/{}/characters/{}/skill/buff/equip/avatar",
@@ -255,8 +280,7 @@ mod character {
         //     );
         // });
         for character in &characters {
-            let result =
-                retry_if_limit_exceeded!(client.character().of(character).buff().avatars().await);
+            let result = client.character().of(character).buff().avatars().await;
 
             // println!("{:#?}", result);
             assert!(result.is_ok());
@@ -264,9 +288,10 @@ mod character {
     }
 
     #[tokio::test]
+    #[ignore = "needs tests/cassettes, record with API_KEY set"]
     async fn buff_creature() {
-        let client = client();
-        let characters = get_characters().await.unwrap();
+        let client = client("character_buff_creature");
+        let characters = get_characters(&client).await.unwrap();
         // characters.iter().for_each(|character| {
         //     println!(
         //         "https://api.neople.co.kr/df/servers/{}/characters/{}/skill/buff/equip/creature",
@@ -274,8 +299,7 @@ mod character {
         //     );
         // });
         for character in &characters {
-            let result =
-                retry_if_limit_exceeded!(client.character().of(character).buff().creature().await);
+            let result = client.character().of(character).buff().creature().await;
 
             // println!("{:#?}", result);
             assert!(result.is_ok());
@@ -0,0 +1,103 @@
+#![cfg(feature = "disk-cache")]
+
+mod common;
+
+use std::{path::PathBuf, time::Duration};
+
+use common::{FakeTransport, ITEM_INFO};
+use df_rs::{
+    cache::{CacheConfig, EndpointFamily},
+    DfClient,
+};
+
+const ITEM_ID: &str = "785e56a0ed4e3efd573da1f56a45217d";
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("df-rs-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn client(fake: &FakeTransport, dir: &PathBuf, config: CacheConfig) -> DfClient {
+    fake.route(&format!("/df/items/{ITEM_ID}"), 200, ITEM_INFO)
+        .route(&format!("/img/items/{ITEM_ID}"), 200, "png");
+    fake.builder().disk_cache(dir, config).build().unwrap()
+}
+
+#[tokio::test]
+async fn survive_restart() {
+    let dir = temp_dir("restart");
+    let fake = FakeTransport::new();
+
+    let client = client(&fake, &dir, Default::default());
+    client.item().id(ITEM_ID).info().await.unwrap();
+    client.image()._item(ITEM_ID).await.unwrap();
+    assert_eq!(fake.request_count(), 2);
+
+    // another client (or process) sharing the directory
+    let client = self::client(&fake, &dir, Default::default());
+    let item = client.item().id(ITEM_ID).info().await.unwrap();
+    let image = client.image()._item(ITEM_ID).await.unwrap();
+    assert_eq!(fake.request_count(), 2);
+    assert_eq!(item.id, ITEM_ID);
+    assert_eq!(&image[..], b"png");
+
+    client.disk_cache().unwrap().clear().await;
+    client.item().id(ITEM_ID).info().await.unwrap();
+    assert_eq!(fake.request_count(), 3);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn expire() {
+    let dir = temp_dir("expire");
+    let fake = FakeTransport::new();
+    let client = client(
+        &fake,
+        &dir,
+        CacheConfig {
+            item: Some(Duration::ZERO),
+            ..Default::default()
+        },
+    );
+
+    client.item().id(ITEM_ID).info().await.unwrap();
+    client.item().id(ITEM_ID).info().await.unwrap();
+    assert_eq!(fake.request_count(), 2);
+
+    client
+        .disk_cache()
+        .unwrap()
+        .invalidate_family(EndpointFamily::Item)
+        .await;
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn promote_to_memory() {
+    let dir = temp_dir("promote");
+    let fake = FakeTransport::new();
+    client(&fake, &dir, Default::default())
+        .item()
+        .id(ITEM_ID)
+        .info()
+        .await
+        .unwrap();
+
+    let client = fake
+        .builder()
+        .cache(Default::default())
+        .disk_cache(&dir, Default::default())
+        .build()
+        .unwrap();
+    client.item().id(ITEM_ID).info().await.unwrap();
+    assert_eq!(client.cache().unwrap().len(), 1);
+
+    // served from memory once the disk entry is gone
+    client.disk_cache().unwrap().clear().await;
+    client.item().id(ITEM_ID).info().await.unwrap();
+    assert_eq!(fake.request_count(), 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
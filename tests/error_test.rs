@@ -0,0 +1,41 @@
+use df_rs::error::{ErrorCode, ResponseError};
+
+fn response_error(code: &str) -> ResponseError {
+    serde_json::from_str(&format!(
+        r#"{{"status":400,"code":"{code}","message":"message"}}"#
+    ))
+    .unwrap()
+}
+
+#[test]
+fn unknown_code() {
+    let e = response_error("DNF123");
+
+    assert_eq!(e.code, ErrorCode::Unknown("DNF123".to_owned()));
+    assert_eq!(e.code.to_string(), "DNF123");
+    assert!(!e.is_retryable());
+}
+
+#[test]
+fn known_code_round_trip() {
+    let e = response_error("DNF980");
+
+    assert_eq!(e.code, ErrorCode::DNF980);
+    assert_eq!(serde_json::to_string(&e.code).unwrap(), r#""DNF980""#);
+}
+
+#[test]
+fn classification() {
+    let e = df_rs::Error::from(response_error("API002"));
+    assert!(e.is_quota_exceeded() && e.is_retryable());
+
+    let e = df_rs::Error::from(response_error("DNF980"));
+    assert!(e.is_maintenance() && !e.is_retryable());
+
+    let e = df_rs::Error::from(response_error("DNF001"));
+    assert!(e.is_not_found());
+
+    let e = df_rs::Error::from(response_error("API003"));
+    assert!(e.is_auth_problem());
+    assert_eq!(e.code(), Some(&ErrorCode::API003));
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use common::{FakeTransport, ITEM_INFO};
+
+// single test, since the registry is shared by the whole binary
+#[tokio::test]
+async fn registry() {
+    assert!(df_rs::try_instance().is_none());
+
+    let fake = FakeTransport::new();
+    fake.route("/df/items/abc", 200, ITEM_INFO);
+    df_rs::configure(fake.builder().api_key("old-key")).unwrap();
+    df_rs::instance().item().id("abc").info().await.unwrap();
+
+    // rotate key
+    df_rs::configure(fake.builder().api_key("new-key")).unwrap();
+    df_rs::instance().item().id("abc").info().await.unwrap();
+
+    let keys: Vec<_> = fake
+        .requests()
+        .iter()
+        .map(|r| r.headers["apikey"].to_str().unwrap().to_owned())
+        .collect();
+    assert_eq!(keys, ["old-key", "new-key"]);
+
+    // invalid builder keeps the previous instance
+    assert!(df_rs::configure(fake.builder().api_key("bad\nkey")).is_err());
+    assert!(df_rs::try_instance().is_some());
+
+    df_rs::global::set_named_instance("batch", fake.client());
+    assert!(df_rs::global::named_instance("batch").is_some());
+    assert_eq!(df_rs::global::instance_names(), ["batch", "default"]);
+    assert!(df_rs::global::remove_named_instance("batch").is_some());
+    assert!(df_rs::global::named_instance("batch").is_none());
+}
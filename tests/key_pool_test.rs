@@ -0,0 +1,91 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{FakeTransport, ITEM_INFO};
+use df_rs::{key_pool::KeySelection, retry::RetryPolicy, DfClient, Error, ErrorKind};
+
+const PATH: &str = "/df/items/785e56a0ed4e3efd573da1f56a45217d";
+
+fn client(fake: &FakeTransport, selection: KeySelection) -> DfClient {
+    fake.builder()
+        .api_keys(["key-aaaa", "key-bbbb"])
+        .key_selection(selection)
+        .key_cooldown(Duration::from_secs(60))
+        .retry(RetryPolicy {
+            jitter: false,
+            ..Default::default()
+        })
+        .build()
+        .unwrap()
+}
+
+async fn item_info(client: &DfClient) {
+    client
+        .item()
+        .id("785e56a0ed4e3efd573da1f56a45217d")
+        .info()
+        .await
+        .unwrap();
+}
+
+fn used_keys(fake: &FakeTransport) -> Vec<String> {
+    fake.requests()
+        .iter()
+        .map(|r| r.headers["apikey"].to_str().unwrap().to_owned())
+        .collect()
+}
+
+#[tokio::test]
+async fn round_robin() {
+    let fake = FakeTransport::new();
+    fake.route(PATH, 200, ITEM_INFO);
+    let client = client(&fake, KeySelection::RoundRobin);
+
+    for _ in 0..4 {
+        item_info(&client).await;
+    }
+
+    assert_eq!(
+        used_keys(&fake),
+        ["key-aaaa", "key-bbbb", "key-aaaa", "key-bbbb"]
+    );
+    let usage = client.key_usage();
+    assert_eq!(usage[0].requests, 2);
+    assert_eq!(usage[1].requests, 2);
+    assert_eq!(usage[0].key, "key-****");
+}
+
+#[tokio::test(start_paused = true)]
+async fn bench_exceeded_key() {
+    let fake = FakeTransport::new();
+    fake.route_error(PATH, 429, "API002")
+        .route(PATH, 200, ITEM_INFO);
+    let client = client(&fake, KeySelection::LeastUsed);
+
+    item_info(&client).await;
+    item_info(&client).await;
+    item_info(&client).await;
+
+    assert_eq!(
+        used_keys(&fake),
+        ["key-aaaa", "key-bbbb", "key-bbbb", "key-bbbb"]
+    );
+    let usage = client.key_usage();
+    assert_eq!(usage[0].errors, 1);
+    assert!(usage[0].benched_for.is_some());
+
+    tokio::time::advance(Duration::from_secs(60)).await;
+    item_info(&client).await;
+    assert_eq!(used_keys(&fake).last().unwrap(), "key-aaaa");
+}
+
+#[test]
+fn invalid_key() {
+    let result = DfClient::builder().api_keys(["ok", "bad\n"]).build();
+
+    assert!(matches!(
+        result.as_ref().map_err(Error::kind),
+        Err(ErrorKind::InvalidApiKey)
+    ));
+}
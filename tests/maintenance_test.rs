@@ -0,0 +1,74 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{FakeTransport, ITEM_INFO};
+use df_rs::{
+    error::ErrorCode,
+    maintenance::{MaintenanceConfig, MaintenanceState},
+    DfClient, Error, ErrorKind,
+};
+
+const PATH: &str = "/df/items/785e56a0ed4e3efd573da1f56a45217d";
+
+fn client(fake: &FakeTransport) -> DfClient {
+    fake.builder()
+        .maintenance(MaintenanceConfig {
+            backoff: Duration::from_secs(300),
+            probe_interval: Duration::from_secs(60),
+        })
+        .build()
+        .unwrap()
+}
+
+async fn info(client: &DfClient) -> Result<df_rs::model::ItemInfo, Error> {
+    client
+        .item()
+        .id("785e56a0ed4e3efd573da1f56a45217d")
+        .info()
+        .await
+}
+
+#[tokio::test(start_paused = true)]
+async fn short_circuit_during_maintenance() {
+    let fake = FakeTransport::new();
+    fake.route_error(PATH, 503, "DNF980")
+        .route_error("/df/servers", 503, "DNF980");
+    let client = client(&fake);
+
+    let first = info(&client).await.unwrap_err();
+    assert_eq!(first.code(), Some(&ErrorCode::DNF980));
+    assert!(client.maintenance().is_maintenance());
+
+    let second = info(&client).await.unwrap_err();
+    assert!(matches!(second.kind(), ErrorKind::Maintenance { .. }));
+    assert!(second.is_maintenance());
+    assert_eq!(fake.request_count(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn probe_until_back() {
+    let fake = FakeTransport::new();
+    fake.route_error(PATH, 503, "DNF980")
+        .route(PATH, 200, ITEM_INFO)
+        .route_error("/df/servers", 503, "DNF980")
+        .route("/df/servers", 200, r#"{"rows":[]}"#);
+    let client = client(&fake);
+    let mut state = client.subscribe_maintenance().unwrap();
+
+    assert!(info(&client).await.is_err());
+    assert!(state.borrow_and_update().is_maintenance());
+
+    // first probe still answers DNF980, second one succeeds
+    let back = state.wait_for(|s| !s.is_maintenance()).await.unwrap();
+    assert_eq!(*back, MaintenanceState::Available);
+    drop(back);
+    let probes = fake
+        .requests()
+        .iter()
+        .filter(|r| r.url.path() == "/df/servers")
+        .count();
+    assert_eq!(probes, 2);
+
+    assert!(info(&client).await.is_ok());
+}
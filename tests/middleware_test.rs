@@ -0,0 +1,92 @@
+mod common;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use common::{FakeTransport, ITEM_INFO};
+use df_rs::{
+    middleware::Middleware,
+    transport::{Request, Response},
+};
+use reqwest::StatusCode;
+
+/// Records calls into a shared log.
+struct Recorder {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Middleware for Recorder {
+    fn before_request(&self, request: &mut Request) -> Option<Response> {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("before {}", self.name));
+        request
+            .headers
+            .insert("x-middleware", self.name.parse().unwrap());
+        None
+    }
+
+    fn after_response(&self, _: &Request, response: &mut Response, _: Duration) {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("after {}", self.name));
+        response
+            .headers
+            .insert("x-seen", self.name.parse().unwrap());
+    }
+}
+
+struct Synthetic;
+
+impl Middleware for Synthetic {
+    fn before_request(&self, request: &mut Request) -> Option<Response> {
+        request
+            .url
+            .path()
+            .starts_with("/img/")
+            .then(|| Response::new(StatusCode::OK, "synthetic"))
+    }
+}
+
+#[tokio::test]
+async fn chain_order_and_request_mutation() {
+    let fake = FakeTransport::new();
+    fake.route("/df/items/abc", 200, ITEM_INFO);
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let client = fake
+        .builder()
+        .middleware(Recorder {
+            name: "a",
+            log: log.clone(),
+        })
+        .middleware(Recorder {
+            name: "b",
+            log: log.clone(),
+        })
+        .build()
+        .unwrap();
+
+    client.item().id("abc").info().await.unwrap();
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["before a", "before b", "after b", "after a"]
+    );
+    assert_eq!(fake.requests()[0].headers["x-middleware"], "b");
+}
+
+#[tokio::test]
+async fn short_circuit_image() {
+    let fake = FakeTransport::new();
+    let client = fake.builder().middleware(Synthetic).build().unwrap();
+
+    let bytes = client.image()._item("abc").await.unwrap();
+
+    assert_eq!(&bytes[..], b"synthetic");
+    assert_eq!(fake.request_count(), 0);
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "mock")]
+
+mod common;
+
+use std::time::Duration;
+
+use common::{AUCTION, CHARACTERS, ITEM_INFO};
+use df_rs::{
+    error::ErrorCode,
+    mock::{Endpoint, Fault, Injection, MockServer},
+    model::Server,
+    retry::RetryPolicy,
+    ErrorKind,
+};
+
+#[tokio::test]
+async fn fixtures() {
+    let server = MockServer::start().await.unwrap();
+    server
+        .fixture(Endpoint::CharacterSearch, CHARACTERS)
+        .fixture(Endpoint::AuctionSearch, AUCTION)
+        .fixture_path("/df/items/abc", ITEM_INFO);
+    let client = server.client();
+
+    let mut handler = client.character();
+    let characters = handler.server(Server::Cain).name("김철수").search().await;
+    assert_eq!(characters.unwrap().len(), 1);
+    assert_eq!(
+        client.auction().name("큐브").search().await.unwrap().len(),
+        1
+    );
+    client.item().id("abc").info().await.unwrap();
+
+    // defaults
+    assert!(client.item().name("x").search().await.unwrap().is_empty());
+    let err = client.item().id("other").info().await.unwrap_err();
+    assert_eq!(err.code(), Some(&ErrorCode::DNF003));
+    let image = client.image()._item("abc").await.unwrap();
+    assert!(image.starts_with(b"\x89PNG"));
+
+    assert_eq!(server.request_count(), 6);
+    assert!(server.requests()[2].starts_with("/df/items/abc"));
+}
+
+#[tokio::test]
+async fn injections() {
+    let server = MockServer::start().await.unwrap();
+    server.fixture(Endpoint::ItemInfo, ITEM_INFO);
+    server
+        .inject(Injection {
+            endpoint: Some(Endpoint::ItemInfo),
+            times: Some(1),
+            ..Fault::QuotaExceeded.into()
+        })
+        .inject(Injection {
+            endpoint: Some(Endpoint::ItemSearch),
+            ..Fault::Maintenance.into()
+        })
+        .inject(Injection {
+            endpoint: Some(Endpoint::AuctionSearch),
+            ..Fault::ServerError(502).into()
+        });
+    let client = server.client();
+
+    let err = client.item().id("abc").info().await.unwrap_err();
+    assert!(err.code().unwrap().is_quota_exceeded(), "{err}");
+    client.item().id("abc").info().await.unwrap();
+
+    let err = client.item().name("x").search().await.unwrap_err();
+    assert!(err.is_maintenance(), "{err}");
+
+    let err = client.auction().name("x").search().await.unwrap_err();
+    assert!(
+        matches!(err.kind(), ErrorKind::UnexpectedResponse(e) if e.status == 502),
+        "{err}"
+    );
+    assert!(err.is_retryable());
+
+    server.clear_injections();
+    client.auction().name("x").search().await.unwrap();
+}
+
+#[tokio::test]
+async fn retried_over_socket() {
+    let server = MockServer::start().await.unwrap();
+    server
+        .fixture(Endpoint::ItemInfo, ITEM_INFO)
+        .inject(Injection {
+            times: Some(2),
+            ..Fault::ServerError(503).into()
+        });
+    let client = server
+        .builder()
+        .retry(RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            jitter: false,
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+
+    client.item().id("abc").info().await.unwrap();
+    assert_eq!(server.request_count(), 3);
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::{FakeTransport, ITEM_INFO};
+use df_rs::{concurrency::Priority, quota::QuotaConfig, ErrorKind};
+
+#[tokio::test]
+async fn counts_per_key() {
+    let fake = FakeTransport::new();
+    for id in ["a", "b", "c"] {
+        fake.route(&format!("/df/items/{id}"), 200, ITEM_INFO);
+    }
+    let client = fake
+        .builder()
+        .api_keys(["key-one", "key-two"])
+        .quota(QuotaConfig::new(10))
+        .build()
+        .unwrap();
+
+    for id in ["a", "b", "c"] {
+        client.item().id(id).info().await.unwrap();
+    }
+
+    let usage = client.quota_usage().unwrap();
+    assert_eq!(usage.requests, [2, 1]);
+    assert_eq!(usage.total(), 3);
+    assert_eq!(usage.remaining(), 17);
+    assert!(!usage.is_exhausted());
+    assert!(usage.resets_in.as_secs() <= 24 * 60 * 60);
+}
+
+#[tokio::test]
+async fn refuses_background_when_exhausted() {
+    let fake = FakeTransport::new();
+    for id in ["a", "b", "c"] {
+        fake.route(&format!("/df/items/{id}"), 200, ITEM_INFO);
+    }
+    let client = fake
+        .builder()
+        .quota(QuotaConfig {
+            refuse_background: true,
+            ..QuotaConfig::new(1)
+        })
+        .build()
+        .unwrap();
+    let background = client.with_priority(Priority::Background);
+
+    background.item().id("a").info().await.unwrap();
+    assert!(client.quota_usage().unwrap().is_exhausted());
+
+    let err = background.item().id("b").info().await.unwrap_err();
+    assert!(
+        matches!(
+            err.kind(),
+            ErrorKind::QuotaExhausted {
+                used: 1,
+                budget: 1,
+                ..
+            }
+        ),
+        "{err}"
+    );
+    // interactive requests are still sent
+    client.item().id("c").info().await.unwrap();
+    assert_eq!(fake.request_count(), 2);
+    assert_eq!(client.quota_usage().unwrap().requests, [2]);
+}
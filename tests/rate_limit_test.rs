@@ -0,0 +1,59 @@
+mod common;
+
+use std::{num::NonZeroU32, time::Duration};
+
+use common::{FakeTransport, ITEM_INFO};
+use df_rs::rate_limit::RateLimit;
+use tokio::time::Instant;
+
+const PATH: &str = "/df/items/785e56a0ed4e3efd573da1f56a45217d";
+
+#[tokio::test(start_paused = true)]
+async fn per_second() {
+    let fake = FakeTransport::new();
+    fake.route(PATH, 200, ITEM_INFO);
+    let client = fake
+        .builder()
+        .rate_limit(RateLimit {
+            per_second: NonZeroU32::new(2),
+            per_minute: None,
+        })
+        .build()
+        .unwrap();
+
+    let start = Instant::now();
+    for _ in 0..5 {
+        // clones share the limiter
+        let mut item = client.clone().item();
+        item.id("785e56a0ed4e3efd573da1f56a45217d")
+            .info()
+            .await
+            .unwrap();
+    }
+
+    // 2 immediately, then 1 per 500ms
+    assert!(start.elapsed() >= Duration::from_millis(1500));
+    assert!(start.elapsed() < Duration::from_millis(2000));
+    assert_eq!(fake.request_count(), 5);
+}
+
+#[tokio::test(start_paused = true)]
+async fn per_minute() {
+    let fake = FakeTransport::new();
+    fake.route("/img/items/abc", 200, "png");
+    let client = fake
+        .builder()
+        .rate_limit(RateLimit {
+            per_second: NonZeroU32::new(10),
+            per_minute: NonZeroU32::new(3),
+        })
+        .build()
+        .unwrap();
+
+    let start = Instant::now();
+    for _ in 0..4 {
+        client.image()._item("abc").await.unwrap();
+    }
+
+    assert!(start.elapsed() >= Duration::from_secs(20));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::{Buffer, FakeTransport, ITEM_INFO};
+use df_rs::{retry::RetryPolicy, Error};
+
+const SECRET: &str = "SECRET-KEY-0123456789";
+
+#[tokio::test(start_paused = true)]
+async fn key_never_leaks() {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let _guard = tracing::subscriber::set_default(
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_writer(move || writer.clone())
+            .finish(),
+    );
+
+    let fake = FakeTransport::new();
+    fake.route_error("/df/items/quota", 429, "API002")
+        .route("/df/items/html", 502, "<html>Bad Gateway</html>")
+        .route("/df/items/broken", 200, r#"{"itemId":1}"#)
+        .route("/df/items/abc", 200, ITEM_INFO);
+    let client = fake
+        .builder()
+        .api_key(SECRET)
+        .retry(RetryPolicy {
+            max_attempts: 2,
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+
+    let mut errors: Vec<Error> = Vec::new();
+    for id in ["quota", "html", "broken"] {
+        errors.push(client.item().id(id).info().await.unwrap_err());
+    }
+    errors.push(client.item().search().await.unwrap_err());
+    client.item().id("abc").info().await.unwrap();
+
+    for error in &errors {
+        assert!(!error.to_string().contains(SECRET), "{error}");
+        assert!(!format!("{error:?}").contains(SECRET), "{error:?}");
+    }
+    assert!(!format!("{client:?}").contains(SECRET));
+    assert!(format!("{client:?}").contains("SECR****"));
+    assert!(!format!("{:?}", fake.requests()).contains(SECRET));
+    assert!(!format!("{:?}", df_rs::DfClient::builder().api_key(SECRET)).contains(SECRET));
+
+    let output = buffer.contents();
+    assert!(
+        output.contains("Request:"),
+        "tracing output is not captured"
+    );
+    assert!(!output.contains(SECRET), "{output}");
+
+    // the key is still sent
+    assert_eq!(fake.requests()[0].headers["apikey"], SECRET);
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use common::{FakeTransport, ITEM_INFO};
+use df_rs::{error::ErrorCode, retry::RetryPolicy, ErrorKind};
+
+const PATH: &str = "/df/items/785e56a0ed4e3efd573da1f56a45217d";
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        jitter: false,
+        ..Default::default()
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn retry_until_success() {
+    let fake = FakeTransport::new();
+    fake.route_error(PATH, 429, "API002")
+        .route_error(PATH, 500, "DNF999")
+        .route(PATH, 200, ITEM_INFO);
+
+    let retries = Arc::new(AtomicU32::new(0));
+    let counter = retries.clone();
+    let client = fake
+        .builder()
+        .retry(RetryPolicy {
+            on_retry: Some(Arc::new(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            })),
+            ..policy()
+        })
+        .build()
+        .unwrap();
+
+    let result = client
+        .item()
+        .id("785e56a0ed4e3efd573da1f56a45217d")
+        .info()
+        .await;
+
+    assert!(result.is_ok());
+    assert_eq!(fake.request_count(), 3);
+    assert_eq!(retries.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn give_up_after_max_attempts() {
+    let fake = FakeTransport::new();
+    fake.route_error(PATH, 429, "API002");
+    let client = fake.builder().retry(policy()).build().unwrap();
+
+    let result = client
+        .item()
+        .id("785e56a0ed4e3efd573da1f56a45217d")
+        .info()
+        .await;
+
+    let error = result.unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Response(e) if e.code == ErrorCode::API002));
+    assert_eq!(error.context().unwrap().attempt, 3);
+    assert_eq!(fake.request_count(), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn give_up_past_deadline() {
+    let fake = FakeTransport::new();
+    fake.route_error(PATH, 429, "API002");
+    let client = fake
+        .builder()
+        .retry(RetryPolicy {
+            max_attempts: 10,
+            deadline: Some(Duration::from_millis(1200)),
+            ..policy()
+        })
+        .build()
+        .unwrap();
+
+    let result = client
+        .item()
+        .id("785e56a0ed4e3efd573da1f56a45217d")
+        .info()
+        .await;
+
+    // 0ms, 500ms, 1500ms(past deadline)
+    assert!(result.is_err());
+    assert_eq!(fake.request_count(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn not_retryable() {
+    let fake = FakeTransport::new();
+    fake.route_error(PATH, 404, "DNF003");
+    let client = fake.builder().retry(policy()).build().unwrap();
+
+    let result = client
+        .item()
+        .id("785e56a0ed4e3efd573da1f56a45217d")
+        .info()
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(fake.request_count(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn backoff_saturates_at_max() {
+    let fake = FakeTransport::new();
+    fake.route_error(PATH, 429, "API002");
+    let client = fake
+        .builder()
+        .retry(RetryPolicy {
+            max_attempts: 100,
+            max_backoff: Duration::from_secs(1),
+            ..policy()
+        })
+        .build()
+        .unwrap();
+
+    let start = tokio::time::Instant::now();
+    let result = client
+        .item()
+        .id("785e56a0ed4e3efd573da1f56a45217d")
+        .info()
+        .await;
+
+    // 500ms + 98 * 1s
+    assert!(result.is_err());
+    assert_eq!(fake.request_count(), 100);
+    assert_eq!(start.elapsed(), Duration::from_millis(98_500));
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use common::{FakeTransport, CHARACTERS, ITEM_INFO};
+use df_rs::{
+    model::Server,
+    schema_drift::{DriftKind, SchemaDriftEntry},
+};
+
+#[tokio::test]
+async fn report_unknown_and_defaulted_fields() {
+    let fake = FakeTransport::new();
+    let body = CHARACTERS
+        .replace(r#""level":110"#, r#""level":110,"fame":12345"#)
+        .replace(
+            r#""jobName":"귀검사(남)""#,
+            r#""jobName":"귀검사(남)","adventureName":null"#,
+        );
+    fake.route("/df/servers/cain/characters", 200, body);
+    let client = fake.builder().schema_drift(true).build().unwrap();
+
+    for _ in 0..2 {
+        let mut handler = client.character();
+        handler
+            .server(Server::Cain)
+            .name("김철수")
+            .search()
+            .await
+            .unwrap();
+    }
+
+    let report = client.schema_drift().unwrap().report();
+    let entry = |path: &str, kind| SchemaDriftEntry {
+        endpoint: "/servers/{server}/characters".to_owned(),
+        path: path.to_owned(),
+        kind,
+        count: 2,
+    };
+    assert_eq!(
+        report.entries,
+        vec![
+            entry("rows[].adventureName", DriftKind::Unknown),
+            entry("rows[].fame", DriftKind::Unknown),
+        ]
+    );
+    assert!(report.to_string().contains("rows[].fame (x2)"));
+}
+
+#[tokio::test]
+async fn disabled_by_default() {
+    let fake = FakeTransport::new();
+    fake.route("/df/servers/cain/characters", 200, CHARACTERS);
+    let client = fake.client();
+
+    let mut handler = client.character();
+    handler
+        .server(Server::Cain)
+        .name("김철수")
+        .search()
+        .await
+        .unwrap();
+
+    assert!(client.schema_drift().is_none());
+}
+
+#[tokio::test]
+async fn report_missing_fields() {
+    let fake = FakeTransport::new();
+    fake.route("/df/items/abc", 200, ITEM_INFO);
+    let client = fake.builder().schema_drift(true).build().unwrap();
+
+    client.item().id("abc").info().await.unwrap();
+
+    let report = client.schema_drift().unwrap().report();
+    let missing: Vec<_> = report
+        .entries
+        .iter()
+        .filter(|e| e.kind == DriftKind::Missing)
+        .map(|e| e.path.as_str())
+        .collect();
+    assert!(missing.contains(&"itemStatus"));
+    assert!(missing.contains(&"talismanInfo"));
+    assert!(report.entries.iter().all(|e| e.endpoint == "/items/{id}"));
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{FakeTransport, ITEM_INFO};
+use df_rs::{error::ErrorCode, Error, ErrorKind};
+use futures::future::join_all;
+
+const ITEM_ID: &str = "785e56a0ed4e3efd573da1f56a45217d";
+
+#[tokio::test(start_paused = true)]
+async fn coalesce_concurrent_requests() {
+    let fake = FakeTransport::new();
+    fake.route(&format!("/df/items/{ITEM_ID}"), 200, ITEM_INFO)
+        .delay(Duration::from_millis(100));
+    let client = fake.client();
+
+    let results = join_all((0..10).map(|_| async {
+        let mut handler = client.item();
+        handler.id(ITEM_ID).info().await
+    }))
+    .await;
+
+    assert!(results.iter().all(|r| r.as_ref().unwrap().id == ITEM_ID));
+    assert_eq!(fake.request_count(), 1);
+
+    // completed requests are not reused
+    client.item().id(ITEM_ID).info().await.unwrap();
+    assert_eq!(fake.request_count(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn share_error() {
+    let fake = FakeTransport::new();
+    fake.route_error("/df/items/unknown", 400, "DNF003")
+        .delay(Duration::from_millis(100));
+    let client = fake.client();
+
+    let results = join_all((0..3).map(|_| async {
+        let mut handler = client.item();
+        handler.id("unknown").info().await
+    }))
+    .await;
+
+    assert!(results
+        .iter()
+        .all(|r| matches!(r.as_ref().map_err(Error::kind), Err(ErrorKind::Response(e)) if e.code == ErrorCode::DNF003)));
+    assert_eq!(fake.request_count(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn disabled() {
+    let fake = FakeTransport::new();
+    fake.route(&format!("/df/items/{ITEM_ID}"), 200, ITEM_INFO)
+        .delay(Duration::from_millis(100));
+    let client = fake.builder().single_flight(false).build().unwrap();
+
+    join_all((0..3).map(|_| async {
+        let mut handler = client.item();
+        handler.id(ITEM_ID).info().await
+    }))
+    .await;
+
+    assert_eq!(fake.request_count(), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn abandoned_request_releases_in_flight_slot() {
+    let fake = FakeTransport::new();
+    fake.delay(Duration::from_secs(10))
+        .route("/df/items/a", 200, ITEM_INFO)
+        .route("/df/items/b", 200, ITEM_INFO);
+    let client = fake.builder().max_in_flight(1).build().unwrap();
+
+    let error = client
+        .item()
+        .id("a")
+        .timeout(Duration::from_secs(1))
+        .info()
+        .await
+        .unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Timeout { .. }));
+
+    let start = tokio::time::Instant::now();
+    client.item().id("b").info().await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(10));
+
+    // the abandoned request is not resumed
+    client.item().id("a").info().await.unwrap();
+    assert_eq!(fake.request_count(), 3);
+}
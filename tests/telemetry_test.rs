@@ -0,0 +1,144 @@
+mod common;
+
+use common::{Buffer, FakeTransport, CHARACTERS};
+use df_rs::{model::Server, retry::RetryPolicy};
+use tracing_subscriber::fmt::format::FmtSpan;
+
+#[tokio::test(start_paused = true)]
+async fn span_fields() {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let _guard = tracing::subscriber::set_default(
+        tracing_subscriber::fmt()
+            .with_span_events(FmtSpan::CLOSE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish(),
+    );
+
+    let fake = FakeTransport::new();
+    fake.route_error("/df/servers/cain/characters", 500, "DNF999")
+        .route("/df/servers/cain/characters", 200, CHARACTERS)
+        .route_error("/df/items/unknown", 400, "DNF003");
+    let client = fake
+        .builder()
+        .retry(RetryPolicy {
+            jitter: false,
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+
+    let mut handler = client.character();
+    handler.server(Server::Cain).name("김철수");
+    handler.search().await.unwrap();
+    client.item().id("unknown").info().await.unwrap_err();
+
+    let output = buffer.contents();
+    let closed: Vec<_> = output.lines().filter(|l| l.contains("close")).collect();
+    assert_eq!(closed.len(), 2, "{output}");
+
+    let search = closed[0];
+    assert!(
+        search.contains(r#"endpoint="/servers/{server}/characters""#),
+        "{search}"
+    );
+    assert!(search.contains(r#"server="cain""#), "{search}");
+    assert!(search.contains("status=200"), "{search}");
+    assert!(search.contains("retries=1"), "{search}");
+    assert!(search.contains("latency_ms=500"), "{search}");
+
+    let info = closed[1];
+    assert!(info.contains("status=400"), "{info}");
+    assert!(info.contains("code=DNF003"), "{info}");
+    assert!(info.contains("retries=0"), "{info}");
+}
+
+#[cfg(feature = "metrics")]
+mod metrics_facade {
+    use std::sync::{atomic::AtomicU64, Arc, Mutex};
+
+    use metrics::{
+        Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+
+    use super::common::{FakeTransport, ITEM_INFO};
+
+    /// Counters and histogram samples keyed by `name{labels}`.
+    #[derive(Default)]
+    struct TestRecorder {
+        counters: Mutex<Vec<(String, Arc<AtomicU64>)>>,
+        histograms: Mutex<Vec<String>>,
+    }
+
+    fn name(key: &Key) -> String {
+        let labels: Vec<_> = key
+            .labels()
+            .map(|l| format!("{}={}", l.key(), l.value()))
+            .collect();
+        format!("{}{{{}}}", key.name(), labels.join(","))
+    }
+
+    impl TestRecorder {
+        fn counter(&self, key: &str) -> u64 {
+            self.counters
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(k, _)| k == key)
+                .map(|(_, v)| v.load(std::sync::atomic::Ordering::SeqCst))
+                .sum()
+        }
+    }
+
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            let value = Arc::new(AtomicU64::new(0));
+            self.counters
+                .lock()
+                .unwrap()
+                .push((name(key), value.clone()));
+            Counter::from_arc(value)
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            self.histograms.lock().unwrap().push(name(key));
+            Histogram::noop()
+        }
+    }
+
+    #[tokio::test]
+    async fn counters_and_histogram() {
+        let recorder = TestRecorder::default();
+        let _guard = metrics::set_default_local_recorder(&recorder);
+
+        let fake = FakeTransport::new();
+        fake.route("/df/items/abc", 200, ITEM_INFO)
+            .route_error("/df/items/unknown", 400, "DNF003");
+        let client = fake.client();
+        client.item().id("abc").info().await.unwrap();
+        client.item().id("unknown").info().await.unwrap_err();
+
+        assert_eq!(
+            recorder.counter("df_requests_total{endpoint=/items/{id}}"),
+            2
+        );
+        assert_eq!(
+            recorder.counter("df_errors_total{endpoint=/items/{id},code=DNF003}"),
+            1
+        );
+        assert!(recorder
+            .histograms
+            .lock()
+            .unwrap()
+            .contains(&"df_request_duration_seconds{endpoint=/items/{id}}".to_owned()));
+    }
+}
@@ -0,0 +1,197 @@
+mod common;
+
+use common::{FakeTransport, API_KEY, AUCTION, CHARACTERS, ITEM_INFO};
+use df_rs::{error::ErrorCode, model::Server, DfClient, Error, ErrorKind};
+
+#[tokio::test]
+async fn character_search() {
+    let fake = FakeTransport::new();
+    fake.route("/df/servers/cain/characters", 200, CHARACTERS);
+
+    let characters = fake
+        .client()
+        .character()
+        .server(Server::Cain)
+        .name("김철수")
+        .limit(5)
+        .search()
+        .await
+        .unwrap();
+
+    assert_eq!(characters[0].id, "c1");
+
+    let request = &fake.requests()[0];
+    assert_eq!(request.headers["apikey"], API_KEY);
+    assert_eq!(
+        request.url.query(),
+        Some("characterName=%EA%B9%80%EC%B2%A0%EC%88%98&limit=5")
+    );
+}
+
+#[tokio::test]
+async fn no_api_key() {
+    let fake = FakeTransport::new();
+    fake.route("/df/items/abc", 200, ITEM_INFO);
+    let client = DfClient::builder()
+        .base_url("http://fake.test/df")
+        .transport(fake.clone())
+        .build()
+        .unwrap();
+
+    client.item().id("abc").info().await.unwrap();
+
+    assert!(!fake.requests()[0].headers.contains_key("apikey"));
+}
+
+#[tokio::test]
+async fn item_info() {
+    let fake = FakeTransport::new();
+    fake.route("/df/items/785e56a0ed4e3efd573da1f56a45217d", 200, ITEM_INFO);
+
+    let item = fake
+        .client()
+        .item()
+        .id("785e56a0ed4e3efd573da1f56a45217d")
+        .info()
+        .await
+        .unwrap();
+
+    assert_eq!(item.name, "무색 큐브 조각");
+}
+
+#[tokio::test]
+async fn auction_search() {
+    let fake = FakeTransport::new();
+    fake.route("/df/auction", 200, AUCTION);
+
+    let rows = fake
+        .client()
+        .auction()
+        .name("무색 큐브 조각")
+        .search()
+        .await
+        .unwrap();
+
+    assert_eq!(rows[0].unit_price, 10);
+}
+
+#[tokio::test]
+async fn image_uses_image_base_url() {
+    let fake = FakeTransport::new();
+    fake.route("/img/items/abc", 200, "png");
+
+    let bytes = fake.client().image()._item("abc").await.unwrap();
+
+    assert_eq!(&bytes[..], b"png");
+}
+
+#[tokio::test]
+async fn error_response() {
+    let fake = FakeTransport::new();
+    fake.route_error("/df/items/unknown", 400, "DNF003");
+
+    let result = fake.client().item().id("unknown").info().await;
+
+    assert!(matches!(
+        result.as_ref().map_err(Error::kind),
+        Err(ErrorKind::Response(e)) if e.code == ErrorCode::DNF003
+    ));
+}
+
+#[tokio::test]
+async fn decode_error() {
+    let fake = FakeTransport::new();
+    fake.route(
+        "/df/servers/cain/characters",
+        200,
+        CHARACTERS.replace("110", r#""high""#),
+    );
+
+    let result = fake
+        .client()
+        .character()
+        .server(Server::Cain)
+        .name("김철수")
+        .search()
+        .await;
+
+    let Err(ErrorKind::Decode(e)) = result.as_ref().map_err(Error::kind) else {
+        panic!("expected decode error: {result:?}");
+    };
+    assert_eq!(e.endpoint, "/df/servers/cain/characters");
+    assert_eq!(e.path, "rows[0].level");
+    assert!(e.snippet.starts_with(r#"{"rows""#));
+    assert_eq!(result.unwrap_err().context().unwrap().attempt, 1);
+}
+
+#[tokio::test]
+async fn non_json_error_body() {
+    let fake = FakeTransport::new();
+    fake.route(
+        "/df/items/abc",
+        502,
+        "<html><body>502 Bad Gateway</body></html>",
+    );
+
+    let result = fake.client().item().id("abc").info().await;
+
+    let Err(ErrorKind::UnexpectedResponse(e)) = result.as_ref().map_err(Error::kind) else {
+        panic!("expected unexpected response: {result:?}");
+    };
+    assert_eq!(e.status, 502);
+    assert!(e.excerpt.contains("502 Bad Gateway"));
+}
+
+#[tokio::test]
+async fn error_context() {
+    let fake = FakeTransport::new();
+    fake.route_error("/df/servers/cain/characters", 400, "DNF001");
+
+    let error = fake
+        .client()
+        .character()
+        .server(Server::Cain)
+        .name("김철수")
+        .search()
+        .await
+        .unwrap_err();
+
+    let context = error.context().expect("context");
+    assert_eq!(context.endpoint, "/servers/{server}/characters");
+    assert!(context
+        .url
+        .starts_with("http://fake.test/df/servers/cain/characters?"));
+    assert!(!context.url.contains(API_KEY));
+    assert!(context
+        .query
+        .contains(&("characterName".to_owned(), "김철수".to_owned())));
+    assert_eq!(context.attempt, 1);
+    assert!(error.to_string().contains("/servers/{server}/characters"));
+}
+
+#[tokio::test]
+async fn validation_error_context() {
+    let fake = FakeTransport::new();
+
+    let error = fake.client().item().search().await.unwrap_err();
+
+    assert!(matches!(error.kind(), ErrorKind::InvalidQueryParameter(_)));
+    assert_eq!(error.context().unwrap().endpoint, "/items");
+    assert_eq!(error.context().unwrap().attempt, 0);
+    assert_eq!(fake.request_count(), 0);
+}
+
+#[tokio::test]
+async fn raw_json() {
+    let fake = FakeTransport::new();
+    fake.route(
+        "/df/items/abc",
+        200,
+        ITEM_INFO.replacen('{', r#"{"newField":[1,2],"#, 1),
+    );
+
+    let raw = fake.client().item().id("abc").info_raw().await.unwrap();
+
+    assert_eq!(raw["newField"], serde_json::json!([1, 2]));
+    assert_eq!(raw["itemId"], "785e56a0ed4e3efd573da1f56a45217d");
+}